@@ -0,0 +1,142 @@
+use std::convert::Infallible;
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+/// A puzzle answer, independent of the type a solution returned it as.
+///
+/// Solutions may return anything that implements `Display` (`u32`, `u64`, `i128`, `String`,
+/// multi-line ASCII art, ...). Integers are kept as `i128`, so `Answer::from(24000_u64)` and
+/// `Answer::from(24000_i32)` compare equal. Everything else is kept as text.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Answer {
+    Number(i128),
+    Text(String),
+}
+
+impl Answer {
+    pub fn from_display<T: Display + ?Sized>(value: &T) -> Self {
+        let Ok(answer) = value.to_string().parse::<Answer>();
+        answer
+    }
+
+    pub fn is_multiline(&self) -> bool {
+        match self {
+            Answer::Number(_) => false,
+            Answer::Text(text) => text.contains('\n'),
+        }
+    }
+
+    /// Single-line representation of the answer, with `\` and line breaks escaped.
+    pub fn serialize(&self) -> String {
+        match self {
+            Answer::Number(number) => number.to_string(),
+            Answer::Text(text) => text.replace('\\', "\\\\").replace('\n', "\\n"),
+        }
+    }
+
+    /// Inverse of [`Answer::serialize`].
+    pub fn deserialize(s: &str) -> Self {
+        let mut text = String::with_capacity(s.len());
+        let mut chars = s.chars();
+
+        while let Some(c) = chars.next() {
+            match (c, chars.clone().next()) {
+                ('\\', Some('n')) => {
+                    text.push('\n');
+                    chars.next();
+                }
+                ('\\', Some('\\')) => {
+                    text.push('\\');
+                    chars.next();
+                }
+                (c, _) => text.push(c),
+            }
+        }
+
+        let Ok(answer) = text.parse::<Answer>();
+        answer
+    }
+}
+
+impl FromStr for Answer {
+    type Err = Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // only treat canonical integers as numbers, `007` or `+7` should survive as written.
+        Ok(match s.parse::<i128>() {
+            Ok(number) if number.to_string() == s => Answer::Number(number),
+            _ => Answer::Text(s.to_string()),
+        })
+    }
+}
+
+impl Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::Number(number) => write!(f, "{}", number),
+            Answer::Text(text) => write!(f, "{}", text),
+        }
+    }
+}
+
+macro_rules! impl_from_integer {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for Answer {
+                fn from(value: $t) -> Self {
+                    Answer::Number(i128::from(value))
+                }
+            }
+        )*
+    };
+}
+
+impl_from_integer!(u8, u16, u32, u64, i8, i16, i32, i64, i128);
+
+impl From<usize> for Answer {
+    fn from(value: usize) -> Self {
+        Answer::Number(value as i128)
+    }
+}
+
+impl From<String> for Answer {
+    fn from(value: String) -> Self {
+        Answer::from_display(&value)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(value: &str) -> Self {
+        Answer::from_display(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_display() {
+        assert_eq!(Answer::from_display(&24000_u32), Answer::Number(24000));
+        assert_eq!(Answer::from_display(&-12_i32), Answer::Number(-12));
+        assert_eq!(Answer::from_display(&u64::MAX), Answer::from(u64::MAX));
+        assert_eq!(Answer::from_display("CMZ"), Answer::Text("CMZ".into()));
+        assert_eq!(Answer::from_display("007"), Answer::Text("007".into()));
+        assert_eq!(Answer::from(24000_u64), Answer::from(24000_i32));
+    }
+
+    #[test]
+    fn test_serialize_roundtrip() {
+        let answers = [
+            Answer::Number(-3),
+            Answer::Text("MCD".into()),
+            Answer::Text("#..#\n#..#\n####".into()),
+            Answer::Text("a\\nb".into()),
+        ];
+
+        for answer in answers {
+            assert!(!answer.serialize().contains('\n'));
+            assert_eq!(Answer::deserialize(&answer.serialize()), answer);
+        }
+    }
+}
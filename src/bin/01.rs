@@ -1,37 +1,42 @@
+use advent_of_code::helpers::parse::{number, parse_input, ParseResult};
+use advent_of_code::helpers::top_k::TopK;
+use nom::branch::alt;
+use nom::character::complete::line_ending;
+use nom::combinator::{eof, opt};
+use nom::multi::fold_many1;
+use nom::sequence::terminated;
+
+/// Total calories carried by one elf, summed while parsing their block of lines.
+fn parse_elf(input: &str) -> ParseResult<'_, u32> {
+    fold_many1(
+        // every number is on its own line.
+        terminated(number::<u32>, alt((line_ending, eof))),
+        || 0,
+        |sum, calories| sum + calories,
+    )(input)
+}
+
+/// The `k` largest per-elf totals, largest first. Elves are streamed through a bounded heap
+/// instead of being collected and sorted.
+fn top_calories(input: &str, k: usize) -> Vec<u32> {
+    let elves = fold_many1(
+        terminated(parse_elf, opt(line_ending)),
+        || TopK::new(k),
+        |mut top, calories| {
+            top.push(calories);
+            top
+        },
+    );
+
+    parse_input(elves, input).into_sorted_vec()
+}
+
 pub fn part_one(input: &str) -> Option<u32> {
-    let result = input
-        .split("\n\n")
-        .map(|calories_per_elf| {
-            calories_per_elf
-                .lines()
-                .map(|line| line.parse::<u32>().unwrap())
-                .sum()
-        })
-        .max()
-        .unwrap();
-
-    Some(result)
+    top_calories(input, 1).first().copied()
 }
 
 pub fn part_two(input: &str) -> Option<u32> {
-    let mut calories_sums: Vec<u32> = input
-        .split("\n\n")
-        .map(|calories_per_elf| {
-            calories_per_elf
-                .lines()
-                .map(|line| line.parse::<u32>().unwrap())
-                .sum()
-        })
-        .collect();
-
-    // largest to smallest
-    calories_sums.sort_by(|a, b| b.cmp(a));
-
-    let result = calories_sums.iter()
-        .take(3)
-        .sum();
-
-    Some(result)
+    Some(top_calories(input, 3).iter().sum())
 }
 
 fn main() {
@@ -55,4 +60,10 @@ mod tests {
         let input = advent_of_code::read_file("examples", 1);
         assert_eq!(part_two(&input), Some(45000));
     }
+
+    #[test]
+    #[should_panic(expected = "expected one of line break or end of input\n --> line 4, column 5")]
+    fn test_number_followed_by_garbage() {
+        part_one("1000\n2000\n\n3000x");
+    }
 }
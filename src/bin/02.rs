@@ -1,4 +1,8 @@
 use std::str::FromStr;
+use advent_of_code::helpers::parse::{label, lines, parse_input, ParseResult};
+use nom::character::complete::{char, one_of};
+use nom::combinator::recognize;
+use nom::sequence::separated_pair;
 use crate::Move::{Paper, Rock, Scissors};
 
 #[derive(Copy, Clone, PartialEq)]
@@ -58,17 +62,21 @@ impl Round {
     }
 }
 
+fn parse_round(input: &str) -> ParseResult<'_, (&str, &str)> {
+    separated_pair(
+        label("opponent move (A, B or C)", recognize(one_of("ABC"))),
+        char(' '),
+        label("player move (X, Y or Z)", recognize(one_of("XYZ"))),
+    )(input)
+}
+
 pub fn part_one(input: &str) -> Option<u32> {
-    let score = input
-        .lines()
-        .map(|s| -> Round {
-            if let Some((opponent_move, player_move)) = s.split_once(" ") {
-                Round {
-                    opponent: Move::from_str(opponent_move).unwrap(),
-                    player: Move::from_str(player_move).unwrap(),
-                }
-            } else {
-                panic!("Could not split move");
+    let score = parse_input(lines(parse_round), input)
+        .into_iter()
+        .map(|(opponent_move, player_move)| -> Round {
+            Round {
+                opponent: Move::from_str(opponent_move).unwrap(),
+                player: Move::from_str(player_move).unwrap(),
             }
         })
         .map(|round| round.get_player_score())
@@ -78,24 +86,21 @@ pub fn part_one(input: &str) -> Option<u32> {
 }
 
 pub fn part_two(input: &str) -> Option<u32> {
-    let score = input.lines()
-        .map(|s| -> Round {
-            if let Some((opponent_move, player_move)) = s.split_once(" ") {
-                Round {
-                    opponent: Move::from_str(opponent_move).unwrap(),
-                    player: match player_move {
-                        "X" => Move::from_str(opponent_move).unwrap().beats(),
-                        "Y" => Move::from_str(opponent_move).unwrap(),
-                        "Z" => match Move::from_str(opponent_move).unwrap() {
-                            Rock => Paper,
-                            Paper => Scissors,
-                            Scissors => Rock,
-                        },
-                        _ => panic!("Unhandled move"),
+    let score = parse_input(lines(parse_round), input)
+        .into_iter()
+        .map(|(opponent_move, player_move)| -> Round {
+            Round {
+                opponent: Move::from_str(opponent_move).unwrap(),
+                player: match player_move {
+                    "X" => Move::from_str(opponent_move).unwrap().beats(),
+                    "Y" => Move::from_str(opponent_move).unwrap(),
+                    "Z" => match Move::from_str(opponent_move).unwrap() {
+                        Rock => Paper,
+                        Paper => Scissors,
+                        Scissors => Rock,
                     },
-                }
-            } else {
-                panic!("Could not split move");
+                    _ => panic!("Unhandled move"),
+                },
             }
         })
         .map(|round| round.get_player_score())
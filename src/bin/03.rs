@@ -1,106 +1,118 @@
-extern crate core;
-
-use std::collections::HashSet;
-use std::str::{FromStr};
-use itertools::{Itertools};
+use std::str::FromStr;
+use advent_of_code::helpers::ascii_set::{letter_priority, AsciiSet};
+use advent_of_code::helpers::parse::{parse_input, ParseResult};
+use nom::character::complete::{alpha1, line_ending};
+use nom::combinator::{map_res, opt};
+use nom::multi::fold_many1;
+use nom::sequence::terminated;
 
 #[derive(Debug)]
 struct Rucksack {
-    first_compartment: HashSet<char>,
-    second_compartment: HashSet<char>,
+    first_compartment: AsciiSet,
+    second_compartment: AsciiSet,
 }
 
 impl Rucksack {
-    fn get_common_item(&self) -> char {
-        *self.first_compartment.intersection(&self.second_compartment)
-            .next()
-            .unwrap()
+    fn get_common_item(&self) -> Option<char> {
+        (self.first_compartment & self.second_compartment).first()
+    }
+
+    fn priority(&self) -> Result<u32, String> {
+        self.get_common_item()
+            .and_then(letter_priority)
+            .ok_or_else(|| "the compartments have no item in common".to_string())
     }
 }
 
 impl FromStr for Rucksack {
-    type Err = ();
+    type Err = String;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let (first_part, second_part) = s.split_at(s.len() / 2);
 
         Ok(Rucksack {
-            first_compartment: first_part
-                .chars()
-                .collect(),
-            second_compartment: second_part
-                .chars()
-                .collect(),
+            first_compartment: first_part.parse()?,
+            second_compartment: second_part.parse()?,
         })
     }
 }
 
-fn ascii_char_to_priority (character: char) -> u32 {
-    let priority = match character {
-        // ascii 'A' starts at 65, mapping to values 27-52
-        _ if character.is_ascii_uppercase() => character as u32 - 65 + 27,
-        // ascii 'a' starts at 97, mapping to values 0-26
-        _ if character.is_ascii_lowercase() => character as u32 - 97 + 1,
-        _ => panic!("Unsupported character"),
-    };
-
-    priority
+/// Sum of the priorities of all rucksacks, added up while parsing so no rucksack is kept.
+fn parse_priorities(input: &str) -> ParseResult<'_, u32> {
+    fold_many1(
+        terminated(
+            map_res(alpha1, |line: &str| line.parse::<Rucksack>()?.priority()),
+            opt(line_ending),
+        ),
+        || 0,
+        |sum, priority| sum + priority,
+    )(input)
 }
 
 pub fn part_one(input: &str) -> Option<u32> {
-    let result = input
-        .lines()
-        .map(|line| Rucksack::from_str(line).unwrap())
-        .map(|rucksack| ascii_char_to_priority(rucksack.get_common_item()))
-        .sum();
-
-    Some(result)
+    Some(parse_input(parse_priorities, input))
 }
 
 // ------------------------
 //          Part 2
 // ------------------------
 
-struct Group {
-    first_elf: HashSet<char>,
-    second_elf: HashSet<char>,
-    third_elf: HashSet<char>,
+/// Running state while reading the rucksacks of part two: the priorities of the complete groups
+/// so far and the rucksacks of the group being read.
+#[derive(Default)]
+struct Groups {
+    priorities: u32,
+    group: [AsciiSet; 3],
+    rucksacks: usize,
+    /// 1-based number of the first group without a common item.
+    without_badge: Option<usize>,
 }
 
-impl Group {
-    fn get_common_item(&self) -> char {
-        let intersection : HashSet<char> = self.first_elf.intersection(
-            &self.second_elf
-        ).copied().collect();
-
-        let intersection: HashSet<char> = intersection.intersection(
-            &self.third_elf
-        ).copied().collect();
+impl Groups {
+    fn add(mut self, rucksack: AsciiSet) -> Self {
+        self.group[self.rucksacks % 3] = rucksack;
+        self.rucksacks += 1;
+
+        if self.rucksacks.is_multiple_of(3) {
+            let [first, second, third] = self.group;
+            match (first & second & third).first().and_then(letter_priority) {
+                Some(priority) => self.priorities += priority,
+                None => {
+                    let group = self.rucksacks / 3;
+                    self.without_badge = self.without_badge.or(Some(group));
+                }
+            }
+        }
 
-        *intersection.iter().next().unwrap()
+        self
     }
 }
 
-pub fn part_two(input: &str) -> Option<u32> {
-    let groups: Vec<Group> = input
-        .lines()
-        .chunks(3)
-        .into_iter()
-        .map(|mut chunk| -> Group {
-            Group {
-                first_elf: chunk.next().unwrap().chars().collect(),
-                second_elf: chunk.next().unwrap().chars().collect(),
-                third_elf: chunk.next().unwrap().chars().collect(),
-            }
-        })
-        .collect();
-
-    let result = groups
-        .iter()
-        .map(|group| ascii_char_to_priority(group.get_common_item()))
-        .sum();
+/// Every three consecutive rucksacks form a group, a leftover rucksack is an error.
+fn parse_groups(input: &str) -> ParseResult<'_, u32> {
+    let groups = fold_many1(
+        terminated(map_res(alpha1, AsciiSet::from_str), opt(line_ending)),
+        Groups::default,
+        Groups::add,
+    );
+
+    map_res(groups, |groups| {
+        if !groups.rucksacks.is_multiple_of(3) {
+            return Err(format!(
+                "{} rucksacks cannot be split into groups of three",
+                groups.rucksacks
+            ));
+        }
+
+        match groups.without_badge {
+            Some(group) => Err(format!("the rucksacks of group {} have no item in common", group)),
+            None => Ok(groups.priorities),
+        }
+    })(input)
+}
 
-    Some(result)
+pub fn part_two(input: &str) -> Option<u32> {
+    Some(parse_input(parse_groups, input))
 }
 
 fn main() {
@@ -112,6 +124,7 @@ fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use advent_of_code::helpers::parse::finish;
 
     #[test]
     fn test_part_one() {
@@ -124,4 +137,26 @@ mod tests {
         let input = advent_of_code::read_file("examples", 3);
         assert_eq!(part_two(&input), Some(70));
     }
+
+    #[test]
+    #[should_panic(expected = "invalid input: 4 rucksacks cannot be split into groups of three")]
+    fn test_incomplete_group() {
+        let input = advent_of_code::read_file("examples", 3);
+        part_two(&input.lines().take(4).collect::<Vec<_>>().join("\n"));
+    }
+
+    #[test]
+    fn test_no_common_item() {
+        let diagnostic = finish(parse_priorities, "vJrwpWtwJgWrhcsFMMfFFhFp\nabcd").unwrap_err();
+        assert_eq!((diagnostic.line, diagnostic.column), (2, 1));
+        assert_eq!(
+            diagnostic.message(),
+            "invalid input: the compartments have no item in common"
+        );
+
+        assert_eq!(
+            finish(parse_groups, "ab\ncd\nef").unwrap_err().message(),
+            "invalid input: the rucksacks of group 1 have no item in common"
+        );
+    }
 }
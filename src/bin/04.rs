@@ -1,64 +1,35 @@
-use std::ops::{RangeInclusive};
-use std::str::FromStr;
-
-#[derive(Debug)]
-struct SectionRange {
-    range: RangeInclusive<i32>,
-}
-
-impl FromStr for SectionRange {
-    type Err = ();
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        if let Some((start, end)) = s.split_once("-") {
-            Ok(SectionRange {
-                range: (start.parse::<i32>().unwrap()..=end.parse::<i32>().unwrap())
-            })
-        } else {
-            panic!("Could not split move");
-        }
-    }
-}
+use advent_of_code::helpers::interval::Interval;
+use advent_of_code::helpers::parse::{interval, lines, parse_input, ParseResult};
+use nom::character::complete::char;
+use nom::combinator::map;
+use nom::sequence::separated_pair;
 
 #[derive(Debug)]
 struct Pair {
-    first: SectionRange,
-    second: SectionRange,
+    first: Interval,
+    second: Interval,
 }
 
 impl Pair {
     fn is_one_fully_contained(&self) -> bool {
-        (self.first.range.start() >= self.second.range.start()
-            && self.first.range.end() <= self.second.range.end())
-        || (self.second.range.start() >= self.first.range.start()
-            && self.second.range.end() <= self.first.range.end())
+        self.first.contains_interval(&self.second) || self.second.contains_interval(&self.first)
     }
 
     fn is_overlapping(&self) -> bool {
-        self.first.range.start() <= self.second.range.end()
-        && self.first.range.end() >= self.second.range.start()
+        self.first.overlaps(&self.second)
     }
 }
 
-impl FromStr for Pair {
-    type Err = ();
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        if let Some((first_range, second_range)) = s.split_once(",") {
-            Ok(Pair {
-                first: SectionRange::from_str(first_range).unwrap(),
-                second: SectionRange::from_str(second_range).unwrap(),
-            })
-        } else {
-            panic!("Could not split move");
-        }
-    }
+fn parse_pair(input: &str) -> ParseResult<'_, Pair> {
+    map(
+        separated_pair(interval, char(','), interval),
+        |(first, second)| Pair { first, second },
+    )(input)
 }
 
 pub fn part_one(input: &str) -> Option<u32> {
-    let contained_pairs: Vec<Pair> = input
-        .lines()
-        .map(|line| Pair::from_str(line).unwrap())
+    let contained_pairs: Vec<Pair> = parse_input(lines(parse_pair), input)
+        .into_iter()
         .filter(|pair| pair.is_one_fully_contained())
         .collect();
 
@@ -66,9 +37,8 @@ pub fn part_one(input: &str) -> Option<u32> {
 }
 
 pub fn part_two(input: &str) -> Option<u32> {
-    let overlapping_pairs: Vec<Pair> = input
-        .lines()
-        .map(|line| Pair::from_str(line).unwrap())
+    let overlapping_pairs: Vec<Pair> = parse_input(lines(parse_pair), input)
+        .into_iter()
         .filter(|pair| pair.is_overlapping())
         .collect();
 
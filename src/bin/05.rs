@@ -1,193 +1,957 @@
-use std::collections::{BTreeMap, HashMap};
-use itertools::enumerate;
-use nom::branch::alt;
-use nom::bytes::complete::tag;
-use nom::character::{complete, is_alphabetic};
-use nom::character::complete::{alpha1, anychar, newline};
-use nom::IResult;
-use nom::multi::separated_list1;
-use nom::sequence::{delimited, tuple};
-
-#[derive(Copy, Clone, Debug)]
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+use std::fmt::{self, Display};
+use std::io::{self, BufRead, Write};
+use std::path::PathBuf;
+use std::time::Duration;
+use std::{fs, process, thread};
+use advent_of_code::helpers::diagnostic::Expected;
+use advent_of_code::helpers::parse::{lines, number, parse_input, token, Error, ParseResult};
+use nom::character::complete::line_ending;
+use nom::sequence::{pair, separated_pair, tuple};
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
 struct MoveInstruction {
     from: u32,
     to: u32,
     amount: u32,
 }
 
-fn parse_instruction(input: &str) -> IResult<&str, MoveInstruction> {
+impl Display for MoveInstruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "move {} from {} to {}", self.amount, self.from, self.to)
+    }
+}
+
+fn parse_instruction(input: &str) -> ParseResult<'_, MoveInstruction> {
     let (input, (_, amount, _, from, _, to)) = tuple((
-        tag("move "),
-        complete::u32,
-        tag(" from "),
-        complete::u32,
-        tag(" to "),
-        complete::u32,
+        token("move "),
+        number,
+        token(" from "),
+        number,
+        token(" to "),
+        number,
     ))(input)?;
 
     Ok((input, MoveInstruction{from, to, amount}))
 }
 
-fn parse_instruction_lines(input: &str) -> IResult<&str, Vec<MoveInstruction>> {
-    let (input, instructions)
-        = separated_list1(newline, parse_instruction)(input)?;
-
-    Ok((input, instructions))
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+struct Stack {
+    label: u32,
+    /// bottom to top, crates are named by what is written between their brackets.
+    crates: Vec<String>,
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
 struct CrateStacks {
-    stacks: BTreeMap<u32, Vec<char>>,
+    /// in the order they are drawn in.
+    stacks: Vec<Stack>,
 }
 
 impl CrateStacks {
-    fn from_grid(grid: Vec<Vec<Option<char>>>) -> Self {
-        let mut stacks: BTreeMap<u32, Vec<char>> = BTreeMap::new();
-
-        for stack in grid.iter().rev() {
-            for (index, &crate_character) in stack.iter().enumerate() {
-                let index = u32::try_from(index).unwrap() + 1;
-                if crate_character != None {
-                    if let Some(col) = stacks.get_mut(&index) {
-                        col.push(crate_character.unwrap());
-                    } else {
-                        stacks.insert(
-                            index,
-                            vec![crate_character.unwrap()]
-                        );
-                    }
-                }
+    fn stack(&self, label: u32) -> Option<&Stack> {
+        self.stacks.iter().find(|stack| stack.label == label)
+    }
+
+    fn stack_mut(&mut self, label: u32) -> Option<&mut Stack> {
+        self.stacks.iter_mut().find(|stack| stack.label == label)
+    }
+
+    /// Checks that `instruction` can be carried out on the current stacks.
+    fn check(&self, instruction: &MoveInstruction) -> Result<(), MoveProblem> {
+        for label in [instruction.from, instruction.to] {
+            if self.stack(label).is_none() {
+                return Err(MoveProblem::UnknownStack(label));
             }
         }
 
-        CrateStacks {
-            stacks
+        let available = self.stack(instruction.from).unwrap().crates.len();
+        if instruction.amount as usize > available {
+            return Err(MoveProblem::NotEnoughCrates {
+                stack: instruction.from,
+                available,
+            });
         }
+
+        Ok(())
     }
 
-    fn execute_instruction_single(&mut self, instruction: &MoveInstruction) {
-        let mut queue = vec![];
+    /// Carries out `instruction`, or leaves the stacks untouched if it is invalid.
+    fn execute(
+        &mut self,
+        crane: &dyn CraneModel,
+        instruction: &MoveInstruction,
+    ) -> Result<(), MoveProblem> {
+        self.check(instruction)?;
 
-        for _ in 1..=instruction.amount {
-            queue.push(
-                self.stacks.get_mut(&instruction.from)
-                    .unwrap()
-                    .pop()
-            )
+        for lift in crane.lifts(instruction.amount) {
+            let source = &mut self.stack_mut(instruction.from).unwrap().crates;
+            let crates = source.split_off(source.len() - lift as usize);
+
+            self.stack_mut(instruction.to).unwrap().crates.extend(crates);
         }
 
-        let target = self.stacks.get_mut(&instruction.to).unwrap();
+        Ok(())
+    }
+
+    /// Carries out the `index`th instruction. In lenient mode, invalid moves are clamped to the
+    /// crates available or skipped, and returned as warnings.
+    fn step(
+        &mut self,
+        crane: &dyn CraneModel,
+        index: usize,
+        instruction: &MoveInstruction,
+        strictness: Strictness,
+    ) -> Result<Option<MoveError>, MoveError> {
+        let Err(problem) = self.execute(crane, instruction) else {
+            return Ok(None);
+        };
+
+        // invalid moves leave the stacks untouched, so this is still the drawing before it.
+        let error = MoveError {
+            index,
+            instruction: *instruction,
+            problem,
+            stacks: self.render(),
+        };
 
-        for crate_character in queue.iter() {
-            if let Some(crate_character) = crate_character {
-                target.push(*crate_character);
+        match (strictness, &error.problem) {
+            (Strictness::Strict, _) => return Err(error),
+            (Strictness::Lenient, MoveProblem::UnknownStack(_)) => {}
+            (Strictness::Lenient, &MoveProblem::NotEnoughCrates { available, .. }) => {
+                let clamped = MoveInstruction {
+                    amount: available as u32,
+                    ..*instruction
+                };
+                self.execute(crane, &clamped)
+                    .expect("clamped moves are valid");
             }
         }
+
+        Ok(Some(error))
     }
 
-    fn execute_instruction_multiple(&mut self, instruction: &MoveInstruction) {
-        let mut tower = vec![];
+    /// Carries out all instructions, see [`CrateStacks::step`]. Returns the warnings of a
+    /// lenient run.
+    fn run(
+        &mut self,
+        crane: &dyn CraneModel,
+        instructions: &[MoveInstruction],
+        strictness: Strictness,
+    ) -> Result<Vec<MoveError>, MoveError> {
+        let mut warnings = vec![];
 
-        for _ in 1..=instruction.amount {
-            tower.push(
-                self.stacks.get_mut(&instruction.from)
-                    .unwrap()
-                    .pop()
-            )
+        for (index, instruction) in instructions.iter().enumerate() {
+            warnings.extend(self.step(crane, index + 1, instruction, strictness)?);
         }
 
-        let target = self.stacks.get_mut(&instruction.to).unwrap();
+        Ok(warnings)
+    }
+
+    /// Every valid move, from any stack holding crates to any other stack.
+    fn moves(&self) -> Vec<MoveInstruction> {
+        let mut moves = vec![];
+
+        for from in &self.stacks {
+            for to in &self.stacks {
+                if from.label == to.label {
+                    continue;
+                }
 
-        for crate_character in tower.iter().rev() {
-            if let Some(crate_character) = crate_character {
-                target.push(*crate_character);
+                moves.extend((1..=from.crates.len() as u32).map(|amount| MoveInstruction {
+                    from: from.label,
+                    to: to.label,
+                    amount,
+                }));
             }
         }
+
+        moves
+    }
+
+    /// The stacks after a valid move.
+    fn after(&self, crane: &dyn CraneModel, instruction: &MoveInstruction) -> CrateStacks {
+        let mut next = self.clone();
+        next.execute(crane, instruction).expect("planned moves are valid");
+        next
+    }
+
+    /// Whether both hold the same stacks and crates, regardless of where the crates are.
+    fn same_crates(&self, other: &CrateStacks) -> bool {
+        fn contents(crate_stacks: &CrateStacks) -> (Vec<u32>, Vec<&str>) {
+            let mut labels: Vec<u32> =
+                crate_stacks.stacks.iter().map(|stack| stack.label).collect();
+            let mut crates: Vec<&str> = crate_stacks
+                .stacks
+                .iter()
+                .flat_map(|stack| stack.crates.iter().map(String::as_str))
+                .collect();
+            labels.sort_unstable();
+            crates.sort_unstable();
+            (labels, crates)
+        }
+
+        contents(self) == contents(other)
     }
 
     fn top_row(&self) -> String {
-        let mut message = vec![];
-        for (_index, stack) in self.stacks.iter() {
-            if let Some(&character) = stack.last() {
-                message.push(character);
+        self.stacks
+            .iter()
+            .filter_map(|stack| stack.crates.last())
+            .map(String::as_str)
+            .collect()
+    }
+
+    /// Draws the stacks like the puzzle input does, labels below the bottom crates:
+    ///
+    /// ```text
+    ///     [D]
+    /// [N] [C]
+    /// [Z] [M] [P]
+    ///  1   2   3
+    /// ```
+    ///
+    /// All columns are as wide as the widest crate or label, separated by a single space.
+    fn render(&self) -> String {
+        let width = self
+            .stacks
+            .iter()
+            .flat_map(|stack| {
+                let crates = stack.crates.iter().map(|name| name.chars().count() + 2);
+                crates.chain([stack.label.to_string().len()])
+            })
+            .max()
+            .unwrap_or(0);
+        let height = self
+            .stacks
+            .iter()
+            .map(|stack| stack.crates.len())
+            .max()
+            .unwrap_or(0);
+
+        let row = |cells: Vec<String>| {
+            let line: Vec<String> = cells.iter().map(|cell| centered(cell, width)).collect();
+            line.join(" ").trim_end().to_string()
+        };
+
+        let mut lines: Vec<String> = (0..height)
+            .rev()
+            .map(|level| {
+                row(self
+                    .stacks
+                    .iter()
+                    .map(|stack| match stack.crates.get(level) {
+                        Some(name) => format!("[{}]", name),
+                        None => String::new(),
+                    })
+                    .collect())
+            })
+            .collect();
+        lines.push(row(self
+            .stacks
+            .iter()
+            .map(|stack| stack.label.to_string())
+            .collect()));
+
+        lines.join("\n")
+    }
+}
+
+/// `text` padded with spaces to `width`, the extra space going to the right.
+fn centered(text: &str, width: usize) -> String {
+    let padding = width.saturating_sub(text.chars().count());
+    let left = padding / 2;
+
+    format!("{}{}{}", " ".repeat(left), text, " ".repeat(padding - left))
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Strictness {
+    /// stop at the first invalid move.
+    Strict,
+    /// clamp moves to the crates available, skip moves between unknown stacks.
+    Lenient,
+}
+
+/// Why a move cannot be carried out.
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum MoveProblem {
+    UnknownStack(u32),
+    NotEnoughCrates { stack: u32, available: usize },
+}
+
+impl MoveProblem {
+    /// What a lenient run does instead.
+    fn resolution(&self) -> String {
+        match self {
+            MoveProblem::UnknownStack(_) => "skipped the move".to_string(),
+            MoveProblem::NotEnoughCrates { available, .. } => {
+                format!("moved {} instead", available)
             }
         }
+    }
+}
 
-        message.iter().collect()
+impl Display for MoveProblem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MoveProblem::UnknownStack(stack) => write!(f, "there is no stack {}", stack),
+            MoveProblem::NotEnoughCrates { stack, available } => {
+                write!(f, "stack {} only holds {} crates", stack, available)
+            }
+        }
     }
 }
 
-fn parse_crate(input: &str) -> IResult<&str, Option<char>> {
-    let (input, c) = alt((
-        tag("   "),
-        delimited(
-            complete::char('['),
-            alpha1,
-            complete::char(']')
-        ),
-    ))(input)?;
+/// An invalid move, with the stacks as they were before it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct MoveError {
+    /// 1-based position in the list of instructions.
+    index: usize,
+    instruction: MoveInstruction,
+    problem: MoveProblem,
+    stacks: String,
+}
+
+impl Display for MoveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "instruction {} ({}): {}\n\n{}",
+            self.index, self.instruction, self.problem, self.stacks
+        )
+    }
+}
+
+/// How a crane carries out a move.
+trait CraneModel {
+    fn name(&self) -> String;
+
+    /// Number of crates in each lift a move of `amount` crates is done in. Every lift takes
+    /// the top crates of the source stack and puts them onto the target in the same order.
+    fn lifts(&self, amount: u32) -> Vec<u32>;
+}
+
+/// Moves one crate at a time.
+struct CrateMover9000;
+
+impl CraneModel for CrateMover9000 {
+    fn name(&self) -> String {
+        "CrateMover 9000".to_string()
+    }
+
+    fn lifts(&self, amount: u32) -> Vec<u32> {
+        vec![1; amount as usize]
+    }
+}
+
+/// Moves any number of crates at once.
+struct CrateMover9001;
+
+impl CraneModel for CrateMover9001 {
+    fn name(&self) -> String {
+        "CrateMover 9001".to_string()
+    }
+
+    fn lifts(&self, amount: u32) -> Vec<u32> {
+        vec![amount]
+    }
+}
+
+/// Moves up to `capacity` crates at once, larger moves are split into several lifts.
+struct LimitedCrane {
+    capacity: u32,
+}
+
+impl CraneModel for LimitedCrane {
+    fn name(&self) -> String {
+        format!("crane lifting up to {} crates", self.capacity)
+    }
 
-    let result = match c {
-        "   " => None,
-        value => {
-            let (input, crate_char) = anychar(value)?;
-            Some(crate_char)
+    fn lifts(&self, amount: u32) -> Vec<u32> {
+        let mut lifts = vec![self.capacity; (amount / self.capacity) as usize];
+        if !amount.is_multiple_of(self.capacity) {
+            lifts.push(amount % self.capacity);
         }
-    };
-    Ok((input, result))
+        lifts
+    }
 }
 
-fn parse_crate_stacks(input: &str) -> IResult<&str, CrateStacks> {
-    let (input, stacks) = separated_list1(
-        newline,
-        separated_list1(
-            tag(" "),
-            parse_crate,
-        ),
-    )(input)?;
+/// Parses `9000`, `9001` or `limited:<capacity>`.
+fn parse_crane(s: &str) -> Result<Box<dyn CraneModel>, String> {
+    match s {
+        "9000" => Ok(Box::new(CrateMover9000)),
+        "9001" => Ok(Box::new(CrateMover9001)),
+        _ => match s.strip_prefix("limited:").map(str::parse) {
+            Some(Ok(capacity)) if capacity > 0 => Ok(Box::new(LimitedCrane { capacity })),
+            _ => Err(format!(
+                "unknown crane \"{}\", expected one of: 9000, 9001, limited:<capacity>",
+                s
+            )),
+        },
+    }
+}
 
-    let crate_stacks = CrateStacks::from_grid(stacks);
+/// The drawing before any move and after every move, each with a caption.
+///
+/// Invalid moves end a strict simulation, a lenient one notes them in the caption.
+fn simulate(
+    mut crate_stacks: CrateStacks,
+    instructions: &[MoveInstruction],
+    crane: &dyn CraneModel,
+    strictness: Strictness,
+) -> Result<Vec<String>, MoveError> {
+    let mut frames = vec![format!(
+        "start, using the {}\n\n{}",
+        crane.name(),
+        crate_stacks.render()
+    )];
 
-    Ok((input, crate_stacks))
+    for (index, instruction) in instructions.iter().enumerate() {
+        let warning = crate_stacks.step(crane, index + 1, instruction, strictness)?;
+
+        let mut caption = format!("{} ({}/{})", instruction, index + 1, instructions.len());
+        if let Some(warning) = warning {
+            caption += &format!(
+                "\nwarning: {}, {}",
+                warning.problem,
+                warning.problem.resolution()
+            );
+        }
+
+        frames.push(format!("{}\n\n{}", caption, crate_stacks.render()));
+    }
+
+    Ok(frames)
 }
 
-pub fn part_one(input: &str) -> Option<String> {
-    let (crate_stacks, instructions) = input.split_once("\n\n").unwrap();
+/// What a plan has to arrive at.
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Goal {
+    /// the crates on top, as read by [`CrateStacks::top_row`].
+    TopRow(String),
+    /// exactly these stacks, in this order.
+    Layout(CrateStacks),
+}
+
+impl Goal {
+    /// Rules out goals that no moves lead to: a layout needs the same stacks and crates, a top
+    /// row has to consist of crates of different stacks.
+    fn is_possible(&self, start: &CrateStacks) -> bool {
+        let mut crates: Vec<&str> = start
+            .stacks
+            .iter()
+            .flat_map(|stack| stack.crates.iter().map(String::as_str))
+            .collect();
+
+        match self {
+            Goal::Layout(layout) => {
+                layout.stacks.len() == start.stacks.len()
+                    && start
+                        .stacks
+                        .iter()
+                        .zip(&layout.stacks)
+                        .all(|(stack, target)| stack.label == target.label)
+                    && start.same_crates(layout)
+            }
+            // some stack always has a crate on top, unless there are none.
+            Goal::TopRow(top_row) if top_row.is_empty() => crates.is_empty(),
+            Goal::TopRow(top_row) => could_be_top_row(top_row, &mut crates, start.stacks.len()),
+        }
+    }
 
-    let (_, mut crate_stacks) = parse_crate_stacks(crate_stacks).unwrap();
+    fn is_reached(&self, crate_stacks: &CrateStacks) -> bool {
+        match self {
+            Goal::TopRow(top_row) => crate_stacks.top_row() == *top_row,
+            Goal::Layout(layout) => crate_stacks == layout,
+        }
+    }
 
-    let (_, instructions) = parse_instruction_lines(instructions).unwrap();
+    /// Lower bound of the moves still needed. A move changes two stacks, so it can fix at most
+    /// two of the stacks that differ from the layout.
+    fn estimate(&self, crate_stacks: &CrateStacks) -> usize {
+        match self {
+            Goal::TopRow(_) => 0,
+            Goal::Layout(layout) => {
+                let differing = crate_stacks
+                    .stacks
+                    .iter()
+                    .zip(&layout.stacks)
+                    .filter(|(stack, target)| stack != target)
+                    .count();
+                differing.div_ceil(2)
+            }
+        }
+    }
+}
 
-    for instruction in instructions.iter() {
-        crate_stacks.execute_instruction_single(instruction);
+/// Whether `top_row` can be split into at most `stacks` names of distinct `crates`.
+fn could_be_top_row(top_row: &str, crates: &mut Vec<&str>, stacks: usize) -> bool {
+    if top_row.is_empty() {
+        return true;
+    }
+    if stacks == 0 {
+        return false;
     }
 
-    Some(crate_stacks.top_row())
+    for index in 0..crates.len() {
+        let Some(rest) = top_row.strip_prefix(crates[index]) else {
+            continue;
+        };
+
+        let name = crates.swap_remove(index);
+        let possible = could_be_top_row(rest, crates, stacks - 1);
+        crates.push(name);
+        let last = crates.len() - 1;
+        crates.swap(index, last);
+
+        if possible {
+            return true;
+        }
+    }
+
+    false
+}
+
+/// Why [`plan`] found no moves.
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum PlanError {
+    Unreachable,
+    /// the goal was not found among this many arrangements.
+    OverBudget(usize),
+}
+
+impl Display for PlanError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PlanError::Unreachable => write!(f, "the goal cannot be reached from these stacks"),
+            PlanError::OverBudget(budget) => {
+                write!(f, "the goal is not among the first {} arrangements explored", budget)
+            }
+        }
+    }
+}
+
+/// A shortest list of moves turning `start` into `goal` with `crane`, exploring at most
+/// `budget` arrangements on the way.
+fn plan(
+    start: &CrateStacks,
+    goal: &Goal,
+    crane: &dyn CraneModel,
+    budget: usize,
+) -> Result<Vec<MoveInstruction>, PlanError> {
+    if !goal.is_possible(start) {
+        return Err(PlanError::Unreachable);
+    }
+
+    // A* over arrangements, every move costs one. Each arrangement is known by its index, with
+    // the fewest moves found to it and the move it was reached by.
+    let mut arrangements = vec![start.clone()];
+    let mut ids = HashMap::from([(start.clone(), 0)]);
+    let mut reached: Vec<(usize, Option<(usize, MoveInstruction)>)> = vec![(0, None)];
+    let mut queue = BinaryHeap::from([Reverse((goal.estimate(start), 0, 0))]);
+    let mut explored = 0;
+
+    while let Some(Reverse((_, moves, id))) = queue.pop() {
+        if moves > reached[id].0 {
+            continue;
+        }
+
+        if goal.is_reached(&arrangements[id]) {
+            let mut plan = vec![];
+            let mut current = id;
+            while let Some((previous, instruction)) = reached[current].1 {
+                plan.push(instruction);
+                current = previous;
+            }
+            plan.reverse();
+
+            return Ok(plan);
+        }
+
+        if explored == budget {
+            return Err(PlanError::OverBudget(budget));
+        }
+        explored += 1;
+
+        for instruction in arrangements[id].moves() {
+            let next = arrangements[id].after(crane, &instruction);
+            let next_id = match ids.get(&next) {
+                Some(&known) if reached[known].0 <= moves + 1 => continue,
+                Some(&known) => known,
+                None => {
+                    arrangements.push(next.clone());
+                    reached.push((usize::MAX, None));
+                    ids.insert(next.clone(), arrangements.len() - 1);
+                    arrangements.len() - 1
+                }
+            };
+
+            reached[next_id] = (moves + 1, Some((id, instruction)));
+            queue.push(Reverse((moves + 1 + goal.estimate(&next), moves + 1, next_id)));
+        }
+    }
+
+    Err(PlanError::Unreachable)
+}
+
+/// A crate or stack label in the drawing, located by its columns.
+struct Cell<'a> {
+    /// the input from the start of the cell on.
+    input: &'a str,
+    text: &'a str,
+    columns: (usize, usize),
+}
+
+/// Splits the first line of `input` into its space separated cells.
+fn cells(input: &str) -> Vec<Cell<'_>> {
+    let line = input.split('\n').next().unwrap_or_default();
+    let mut cells = vec![];
+    let mut start = None;
+
+    for (column, (offset, c)) in line.char_indices().chain([(line.len(), ' ')]).enumerate() {
+        match (start, c.is_whitespace()) {
+            (None, false) => start = Some((column, offset)),
+            (Some((first, begin)), true) => {
+                cells.push(Cell {
+                    input: &input[begin..],
+                    text: &line[begin..offset],
+                    columns: (first, column - 1),
+                });
+                start = None;
+            }
+            _ => {}
+        }
+    }
+
+    cells
+}
+
+fn failure<T>(input: &str, expected: Expected) -> ParseResult<'_, T> {
+    Err(nom::Err::Failure(Error::expected(input, expected)))
+}
+
+/// The drawing of the stacks, ending in the row of stack labels. Crates are assigned to the
+/// label they are drawn above, so stacks may be numbered in any order, crates can have names
+/// of several characters and trailing whitespace does not matter.
+fn parse_crate_stacks(input: &str) -> ParseResult<'_, CrateStacks> {
+    // the drawing ends at the blank line, with either line ending. Its lines may end in `\r`,
+    // which is whitespace to `cells`.
+    let blank_line = [input.find("\r\n\r\n"), input.find("\n\n")].into_iter().flatten().min();
+    let Some((drawing, rest)) = blank_line.map(|index| input.split_at(index)) else {
+        // point after the label row, the first line without crates.
+        let label_row = [0]
+            .into_iter()
+            .chain(input.match_indices('\n').map(|(index, _)| index + 1))
+            .find(|&start| !input[start..].split('\n').next().unwrap().contains('['))
+            .unwrap_or(0);
+        let end = input[label_row..]
+            .find(['\r', '\n'])
+            .map_or(input.len(), |index| label_row + index);
+
+        return failure(&input[end..], Expected::Label("blank line after the drawing"));
+    };
+
+    // every line together with the input following it, for errors to point into.
+    let mut lines: Vec<&str> = drawing
+        .match_indices('\n')
+        .map(|(index, _)| &input[index + 1..])
+        .collect();
+    lines.insert(0, input);
+    let label_line = lines.pop().unwrap();
+
+    let mut stacks = vec![];
+    let mut columns = vec![];
+    for cell in cells(label_line) {
+        let label = match cell.text.parse::<u32>() {
+            Ok(label) => label,
+            Err(_) => return failure(cell.input, Expected::Label("stack number")),
+        };
+        if stacks.iter().any(|stack: &Stack| stack.label == label) {
+            return failure(cell.input, Expected::Invalid(format!("stack {} exists twice", label)));
+        }
+
+        stacks.push(Stack { label, crates: vec![] });
+        columns.push(cell.columns);
+    }
+
+    if stacks.is_empty() {
+        return failure(label_line, Expected::Label("stack number"));
+    }
+
+    for (level, line) in lines.into_iter().rev().enumerate() {
+        let mut filled = vec![false; stacks.len()];
+
+        for cell in cells(line) {
+            let name = cell
+                .text
+                .strip_prefix('[')
+                .and_then(|name| name.strip_suffix(']'))
+                .filter(|name| !name.is_empty() && !name.contains(['[', ']']));
+            let Some(name) = name else {
+                return failure(cell.input, Expected::Label("crate"));
+            };
+
+            let overlapping: Vec<usize> = (0..stacks.len())
+                .filter(|&index| {
+                    let (first, last) = columns[index];
+                    first <= cell.columns.1 && cell.columns.0 <= last
+                })
+                .collect();
+
+            let index = match overlapping.as_slice() {
+                [index] if !filled[*index] => *index,
+                _ => {
+                    return failure(
+                        cell.input,
+                        Expected::Invalid("crate is not above exactly one stack label".into()),
+                    )
+                }
+            };
+
+            let stack = &mut stacks[index];
+            if stack.crates.len() < level {
+                return failure(
+                    cell.input,
+                    Expected::Invalid("crate is floating above an empty spot".into()),
+                );
+            }
+
+            stack.crates.push(name.to_string());
+            filled[index] = true;
+        }
+    }
+
+    Ok((rest, CrateStacks { stacks }))
+}
+
+fn parse_puzzle(input: &str) -> ParseResult<'_, (CrateStacks, Vec<MoveInstruction>)> {
+    separated_pair(
+        parse_crate_stacks,
+        pair(line_ending, line_ending),
+        lines(parse_instruction),
+    )(input)
+}
+
+/// Top row after all moves. The puzzle has no answer for invalid moves, so this panics with
+/// the first one instead.
+fn solve(input: &str, crane: &dyn CraneModel) -> String {
+    let (mut crate_stacks, instructions) = parse_input(parse_puzzle, input);
+
+    if let Err(error) = crate_stacks.run(crane, &instructions, Strictness::Strict) {
+        panic!("invalid move, {}", error);
+    }
+
+    crate_stacks.top_row()
+}
+
+pub fn part_one(input: &str) -> Option<String> {
+    Some(solve(input, &CrateMover9000))
 }
 
 pub fn part_two(input: &str) -> Option<String> {
-    let (crate_stacks, instructions) = input.split_once("\n\n").unwrap();
+    Some(solve(input, &CrateMover9001))
+}
 
-    let (_, mut crate_stacks) = parse_crate_stacks(crate_stacks).unwrap();
+/// Alternative output instead of the answers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Mode {
+    Simulate,
+    /// list every invalid move, continuing leniently after each.
+    Check,
+    /// print a puzzle whose moves turn the stacks into `--target` or `--top`.
+    Plan,
+}
 
-    let (_, instructions) = parse_instruction_lines(instructions).unwrap();
+struct Args {
+    example: bool,
+    mode: Option<Mode>,
+    lenient: bool,
+    crane: Box<dyn CraneModel>,
+    /// wait for enter between frames.
+    step: bool,
+    /// pause between frames when not stepping.
+    delay: Duration,
+    /// write all frames to this file instead of playing them.
+    frames: Option<PathBuf>,
+    /// drawing of the stacks to plan for.
+    target: Option<PathBuf>,
+    /// top row to plan for.
+    top: Option<String>,
+    /// arrangements to explore before giving up on a plan.
+    budget: usize,
+}
+
+fn parse_args() -> Result<Args, pico_args::Error> {
+    let mut args = pico_args::Arguments::from_env();
+    Ok(Args {
+        example: args.contains("--example"),
+        crane: args
+            .opt_value_from_fn("--crane", parse_crane)?
+            .unwrap_or_else(|| Box::new(CrateMover9000)),
+        step: args.contains("--step"),
+        delay: Duration::from_millis(args.opt_value_from_str("--delay")?.unwrap_or(500)),
+        frames: args.opt_value_from_os_str("--frames", |s| Ok::<_, String>(PathBuf::from(s)))?,
+        lenient: args.contains("--lenient"),
+        target: args.opt_value_from_os_str("--target", |s| Ok::<_, String>(PathBuf::from(s)))?,
+        top: args.opt_value_from_str("--top")?,
+        budget: args.opt_value_from_str("--budget")?.unwrap_or(100_000),
+        mode: match args.opt_free_from_str::<String>()?.as_deref() {
+            None => None,
+            Some("simulate") => Some(Mode::Simulate),
+            Some("check") => Some(Mode::Check),
+            Some("plan") => Some(Mode::Plan),
+            Some(mode) => {
+                return Err(pico_args::Error::ArgumentParsingFailed {
+                    cause: format!(
+                        "unknown mode \"{}\", expected one of: simulate, check, plan",
+                        mode
+                    ),
+                })
+            }
+        },
+    })
+}
+
+fn play(frames: &[String], step: bool, delay: Duration) -> io::Result<()> {
+    let mut stdin = io::stdin().lock();
+    let mut stdout = io::stdout().lock();
+
+    for (index, frame) in frames.iter().enumerate() {
+        // clear the screen and move the cursor to the top left.
+        writeln!(stdout, "\x1b[2J\x1b[H{}", frame)?;
+
+        if index + 1 == frames.len() {
+            break;
+        }
+
+        if step {
+            write!(stdout, "\n[enter] next move, [q] quit ")?;
+            stdout.flush()?;
 
-    for instruction in instructions.iter() {
-        crate_stacks.execute_instruction_multiple(instruction);
+            let mut line = String::new();
+            if stdin.read_line(&mut line)? == 0 || line.trim() == "q" {
+                break;
+            }
+        } else {
+            stdout.flush()?;
+            thread::sleep(delay);
+        }
     }
 
-    Some(crate_stacks.top_row())
+    Ok(())
 }
 
 fn main() {
-    let input = &advent_of_code::read_file("inputs", 5);
-    advent_of_code::solve!(1, part_one, input);
-    advent_of_code::solve!(2, part_two, input);
+    let args = match parse_args() {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("Failed to process arguments: {}", e);
+            eprintln!(
+                "Usage: `cargo solve 05 -- [simulate|check|plan] [--crane 9000|9001|limited:<n>] \
+                 [--lenient] [--step] [--delay <ms>] [--frames <file>] \
+                 [--target <file>|--top <row>] [--budget <n>] [--example]`"
+            );
+            process::exit(1);
+        }
+    };
+
+    let folder = if args.example { "examples" } else { "inputs" };
+    let input = &advent_of_code::read_file(folder, 5);
+
+    let Some(mode) = args.mode else {
+        advent_of_code::solve!(1, part_one, input);
+        advent_of_code::solve!(2, part_two, input);
+        return;
+    };
+
+    let (mut crate_stacks, instructions) = parse_input(parse_puzzle, input);
+
+    if mode == Mode::Plan {
+        let goal = match (&args.target, &args.top) {
+            (Some(file), None) => {
+                let drawing = fs::read_to_string(file).unwrap_or_else(|e| {
+                    eprintln!("Failed to read {}: {}", file.display(), e);
+                    process::exit(1);
+                });
+                // the drawing parser expects the blank line in front of the moves.
+                let drawing = drawing.trim_end().to_string() + "\n\n";
+                Goal::Layout(parse_input(parse_crate_stacks, &drawing))
+            }
+            (None, Some(top)) => Goal::TopRow(top.clone()),
+            _ => {
+                eprintln!("Pass either `--target <file>` or `--top <row>` to plan for.");
+                process::exit(1);
+            }
+        };
+
+        match plan(&crate_stacks, &goal, args.crane.as_ref(), args.budget) {
+            Ok(moves) if moves.is_empty() => eprintln!("The stacks already match the goal."),
+            Ok(moves) => {
+                let moves: Vec<String> = moves.iter().map(MoveInstruction::to_string).collect();
+                println!("{}\n\n{}", crate_stacks.render(), moves.join("\n"));
+            }
+            Err(error) => {
+                eprintln!("No plan found, {}.", error);
+                process::exit(1);
+            }
+        }
+        return;
+    }
+
+    if mode == Mode::Check {
+        let warnings = crate_stacks
+            .run(args.crane.as_ref(), &instructions, Strictness::Lenient)
+            .expect("lenient runs do not fail");
+
+        for warning in &warnings {
+            println!(
+                "warning: instruction {} ({}): {}, {}\n\n{}\n",
+                warning.index,
+                warning.instruction,
+                warning.problem,
+                warning.problem.resolution(),
+                warning.stacks
+            );
+        }
+        println!(
+            "{} invalid moves, top row: {}",
+            warnings.len(),
+            crate_stacks.top_row()
+        );
+        return;
+    }
+
+    let strictness = if args.lenient {
+        Strictness::Lenient
+    } else {
+        Strictness::Strict
+    };
+    let frames = match simulate(crate_stacks, &instructions, args.crane.as_ref(), strictness) {
+        Ok(frames) => frames,
+        Err(error) => {
+            eprintln!("Invalid move, {}", error);
+            eprintln!("Pass `--lenient` to clamp or skip invalid moves.");
+            process::exit(1);
+        }
+    };
+
+    let result = match &args.frames {
+        Some(file) => fs::write(file, frames.join("\n\n") + "\n"),
+        None => play(&frames, args.step, args.delay),
+    };
+
+    if let Err(e) = result {
+        eprintln!("Failed to show the simulation: {}", e);
+        process::exit(1);
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use advent_of_code::helpers::parse::finish;
 
     #[test]
     fn test_part_one() {
@@ -200,4 +964,243 @@ mod tests {
         let input = advent_of_code::read_file("examples", 5);
         assert_eq!(&part_two(&input).unwrap(), "MCD");
     }
+
+    #[test]
+    fn test_crane_models() {
+        let input = advent_of_code::read_file("examples", 5);
+        let (crate_stacks, instructions) = parse_input(parse_puzzle, &input);
+        let top_row = |crane: &dyn CraneModel| {
+            let mut crate_stacks = crate_stacks.clone();
+            crate_stacks
+                .run(crane, &instructions, Strictness::Strict)
+                .unwrap();
+            crate_stacks.top_row()
+        };
+
+        assert_eq!(top_row(&CrateMover9000), "CMZ");
+        assert_eq!(top_row(&CrateMover9001), "MCD");
+        assert_eq!(top_row(&LimitedCrane { capacity: 1 }), "CMZ");
+        assert_eq!(top_row(&LimitedCrane { capacity: 3 }), "MCD");
+        // `move 3 from 1 to 3` lifts [N] [D] first, then puts [Z] on top of them.
+        assert_eq!(top_row(&LimitedCrane { capacity: 2 }), "MCZ");
+
+        assert_eq!(LimitedCrane { capacity: 2 }.lifts(5), vec![2, 2, 1]);
+        assert_eq!(parse_crane("limited:4").unwrap().name(), "crane lifting up to 4 crates");
+        assert!(parse_crane("limited:0").is_err());
+        assert!(parse_crane("9002").is_err());
+    }
+
+    #[test]
+    fn test_invalid_moves() {
+        let input = advent_of_code::read_file("examples", 5);
+        let (crate_stacks, _) = parse_input(parse_puzzle, &input);
+        let instructions = [
+            MoveInstruction { from: 2, to: 1, amount: 1 },
+            MoveInstruction { from: 1, to: 3, amount: 4 },
+            MoveInstruction { from: 3, to: 4, amount: 1 },
+        ];
+
+        let error = crate_stacks
+            .clone()
+            .run(&CrateMover9000, &instructions, Strictness::Strict)
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "instruction 2 (move 4 from 1 to 3): stack 1 only holds 3 crates\n\n\
+             [D]\n\
+             [N] [C]\n\
+             [Z] [M] [P]\n \
+             1   2   3"
+        );
+
+        let mut lenient = crate_stacks.clone();
+        let warnings = lenient
+            .run(&CrateMover9000, &instructions, Strictness::Lenient)
+            .unwrap();
+        assert_eq!(
+            warnings.iter().map(|warning| (warning.index, &warning.problem)).collect::<Vec<_>>(),
+            vec![
+                (2, &MoveProblem::NotEnoughCrates { stack: 1, available: 3 }),
+                (3, &MoveProblem::UnknownStack(4)),
+            ]
+        );
+        assert_eq!(warnings[0].problem.resolution(), "moved 3 instead");
+        assert_eq!(lenient.top_row(), "CZ");
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid move, instruction 1 (move 1 from 7 to 1)")]
+    fn test_refuses_invalid_moves() {
+        part_one("[A]\n 1\n\nmove 1 from 7 to 1");
+    }
+
+    fn parse_drawing(drawing: &str) -> Result<CrateStacks, String> {
+        let input = format!("{}\n\nmove 1 from 1 to 1", drawing);
+        finish(parse_puzzle, &input)
+            .map(|(crate_stacks, _)| crate_stacks)
+            .map_err(|diagnostic| diagnostic.message())
+    }
+
+    #[test]
+    fn test_parse_many_stacks() {
+        let drawing = [
+            "                                        [K]",
+            "[A]                                     [L] [M]",
+            "[B] [C] [D] [E] [F] [G] [H] [I] [J] [X] [Y] [Z]",
+            " 1   2   3   4   5   6   7   8   9  10  11  12",
+        ]
+        .join("\n");
+        let drawing = drawing.as_str();
+        let crate_stacks = parse_drawing(drawing).unwrap();
+
+        assert_eq!(crate_stacks.stacks.len(), 12);
+        assert_eq!(crate_stacks.stack(11).unwrap().crates, vec!["Y", "L", "K"]);
+        assert_eq!(crate_stacks.top_row(), "ACDEFGHIJXKM");
+        assert_eq!(crate_stacks.render(), drawing);
+    }
+
+    #[test]
+    fn test_parse_multi_character_crates() {
+        let drawing = "   [Fe]  \n[Cu] [Au]      \n 3    1    2  ";
+        let crate_stacks = parse_drawing(drawing).unwrap();
+
+        assert_eq!(
+            crate_stacks.stacks,
+            vec![
+                Stack { label: 3, crates: vec!["Cu".into()] },
+                Stack { label: 1, crates: vec!["Au".into(), "Fe".into()] },
+                Stack { label: 2, crates: vec![] },
+            ]
+        );
+
+        let rendered = crate_stacks.render();
+        assert_eq!(rendered, "     [Fe]\n[Cu] [Au]\n 3    1    2");
+        assert_eq!(parse_drawing(&rendered), Ok(crate_stacks));
+    }
+
+    #[test]
+    fn test_parse_drawing_errors() {
+        assert_eq!(parse_drawing("[A]\n 1  x"), Err("expected stack number".into()));
+        assert_eq!(parse_drawing("[A\n 1"), Err("expected crate".into()));
+        assert_eq!(
+            parse_drawing("[A]\n 1   1"),
+            Err("invalid input: stack 1 exists twice".into())
+        );
+        assert_eq!(
+            parse_drawing("    [A]\n[B]\n 1   2"),
+            Err("invalid input: crate is floating above an empty spot".into())
+        );
+        assert_eq!(
+            parse_drawing("  [A]\n 1   2"),
+            Err("invalid input: crate is not above exactly one stack label".into())
+        );
+
+        let diagnostic = finish(parse_puzzle, "[A]\n[B] [C}\n 1   2\n\nmove 1 from 1 to 2");
+        let diagnostic = diagnostic.unwrap_err();
+        assert_eq!((diagnostic.line, diagnostic.column), (2, 5));
+
+        for (input, location) in [
+            ("[A]\n 1", (2, 3)),
+            ("[A]\n 1\nmove 1 from 1 to 1", (2, 3)),
+            (" 1   2\nmove 1 from 1 to 2", (1, 7)),
+        ] {
+            let diagnostic = finish(parse_puzzle, input).unwrap_err();
+            assert_eq!(diagnostic.message(), "expected blank line after the drawing");
+            assert_eq!((diagnostic.line, diagnostic.column), location);
+        }
+    }
+
+    #[test]
+    fn test_crlf() {
+        let input = advent_of_code::read_file("examples", 5).replace('\n', "\r\n");
+        assert_eq!(&part_one(&input).unwrap(), "CMZ");
+        assert_eq!(&part_two(&input).unwrap(), "MCD");
+
+        let (crate_stacks, _) = parse_input(parse_puzzle, &input);
+        assert_eq!(crate_stacks.stack(2).unwrap().crates, vec!["M", "C", "D"]);
+    }
+
+    #[test]
+    fn test_render() {
+        let input = advent_of_code::read_file("examples", 5);
+        let (crate_stacks, _) = parse_input(parse_puzzle, &input);
+        let (drawing, _) = input.split_once("\n\n").unwrap();
+
+        assert_eq!(crate_stacks.render(), drawing);
+    }
+
+    #[test]
+    fn test_simulate() {
+        let input = advent_of_code::read_file("examples", 5);
+        let (crate_stacks, instructions) = parse_input(parse_puzzle, &input);
+        let frames = simulate(crate_stacks, &instructions, &CrateMover9000, Strictness::Strict)
+            .unwrap();
+
+        assert_eq!(frames.len(), 5);
+        assert_eq!(
+            frames[1],
+            "move 1 from 2 to 1 (1/4)\n\n\
+             [D]\n\
+             [N] [C]\n\
+             [Z] [M] [P]\n \
+             1   2   3"
+        );
+        assert!(frames[4].ends_with(
+            "        [Z]\n        [N]\n        [D]\n[C] [M] [P]\n 1   2   3"
+        ));
+    }
+
+    #[test]
+    fn test_plan() {
+        let input = advent_of_code::read_file("examples", 5);
+        let (crate_stacks, instructions) = parse_input(parse_puzzle, &input);
+        let replay = |crane: &dyn CraneModel, moves: &[MoveInstruction]| {
+            let mut crate_stacks = crate_stacks.clone();
+            crate_stacks.run(crane, moves, Strictness::Strict).unwrap();
+            crate_stacks
+        };
+
+        let moves = plan(&crate_stacks, &Goal::TopRow("CMZ".into()), &CrateMover9000, 1000);
+        let moves = moves.unwrap();
+        assert_eq!(moves.len(), 2);
+        assert_eq!(replay(&CrateMover9000, &moves).top_row(), "CMZ");
+
+        let layout = replay(&CrateMover9001, &instructions);
+        let moves = plan(&crate_stacks, &Goal::Layout(layout.clone()), &CrateMover9001, 10_000);
+        let moves = moves.unwrap();
+        assert!(moves.len() <= instructions.len());
+        assert_eq!(replay(&CrateMover9001, &moves), layout);
+
+        let moves = plan(&crate_stacks, &Goal::TopRow("NDP".into()), &CrateMover9000, 1);
+        assert_eq!(moves, Ok(vec![]));
+        assert_eq!(
+            plan(&crate_stacks, &Goal::TopRow("CMZ".into()), &CrateMover9000, 5),
+            Err(PlanError::OverBudget(5))
+        );
+
+        // ruled out before searching, however large the budget.
+        for top_row in ["CMX", "CMZD", "CC", ""] {
+            let goal = Goal::TopRow(top_row.into());
+            let moves = plan(&crate_stacks, &goal, &CrateMover9000, usize::MAX);
+            assert_eq!(moves, Err(PlanError::Unreachable), "{}", top_row);
+        }
+    }
+
+    #[test]
+    fn test_plan_unreachable() {
+        let (crate_stacks, _) = parse_input(parse_puzzle, "[A]\n 1   2\n\nmove 1 from 1 to 2");
+        let crane = &CrateMover9000;
+
+        assert_eq!(
+            plan(&crate_stacks, &Goal::TopRow("B".into()), crane, 1000),
+            Err(PlanError::Unreachable)
+        );
+
+        let mut layout = crate_stacks.clone();
+        layout.stacks[1].crates.push("B".into());
+        assert_eq!(
+            plan(&crate_stacks, &Goal::Layout(layout), crane, 1000),
+            Err(PlanError::Unreachable)
+        );
+    }
 }
@@ -1,29 +1,9 @@
-use std::collections::VecDeque;
-use itertools::Itertools;
+use advent_of_code::helpers::window::first_distinct_byte_window;
 
 fn search_distinct_character_block(input: &str, block_length: usize) -> Option<u32> {
-    if input.len() < block_length {
-        return None;
-    }
-
-    let mut deq = VecDeque::from_iter(input[0..block_length].chars());
-    let mut block_start = u32::try_from(block_length).unwrap();
-
-    for char in input.chars().skip(block_length) {
-        if deq.iter().all_unique() {
-            break;
-        }
+    let window = first_distinct_byte_window(input.trim_end().as_bytes(), block_length)?;
 
-        deq.push_back(char);
-        deq.pop_front();
-        block_start += 1;
-    }
-
-    if usize::try_from(block_start).unwrap() < input.len() {
-        Some(block_start)
-    } else {
-        None
-    }
+    Some(u32::try_from(window.end()).unwrap())
 }
 
 pub fn part_one(input: &str) -> Option<u32> {
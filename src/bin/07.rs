@@ -1,196 +1,1021 @@
+use advent_of_code::helpers::parse::{lines, number, parse_input, token, ParseResult};
+use advent_of_code::helpers::top_k;
 use nom::branch::alt;
-use nom::bytes::complete::tag;
-use nom::character::complete::digit1;
-use nom::IResult;
-use std::collections::HashMap;
+use nom::character::complete::{char, not_line_ending};
+use nom::combinator::{map, value};
+use nom::sequence::{preceded, separated_pair};
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::{self, Display};
+use std::fs::OpenOptions;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::{fs, io, process};
 
-#[derive(Debug)]
+/// Index of a directory in [`FileSystem::directories`].
+type DirectoryId = usize;
+
+const ROOT: DirectoryId = 0;
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 struct File {
-    size: i32,
+    name: String,
+    size: u64,
+}
+
+#[derive(Debug)]
+struct Directory {
+    name: String,
+    parent: Option<DirectoryId>,
+    children: BTreeMap<String, DirectoryId>,
+    files: Vec<File>,
+    /// Size of all files in this directory and its subdirectories.
+    size: u64,
 }
 
+/// Directory tree reconstructed from a terminal transcript.
+///
+/// Directories are stored in an arena and refer to each other by index, a directory is always
+/// created after its parent.
 #[derive(Debug)]
 struct FileSystem {
-    cwd: String,
-    files: HashMap<String, Vec<File>>,
+    directories: Vec<Directory>,
+    cwd: DirectoryId,
 }
 
 impl FileSystem {
+    fn new() -> Self {
+        FileSystem {
+            directories: vec![Directory {
+                name: String::new(),
+                parent: None,
+                children: BTreeMap::new(),
+                files: vec![],
+                size: 0,
+            }],
+            cwd: ROOT,
+        }
+    }
+
+    /// Replays a transcript and checks that it describes a single, unchanging filesystem.
+    fn from_transcript(instructions: &[Instruction]) -> Result<Self, Vec<TranscriptError>> {
+        let mut filesystem = FileSystem::new();
+        let mut errors = vec![];
+
+        // the `$ ls` whose output is being read: its line and the entries listed so far.
+        let mut listing: Option<(usize, Vec<Entry>)> = None;
+        // the first listing of every directory, later ones have to match it.
+        let mut listed: HashMap<DirectoryId, (usize, Vec<Entry>)> = HashMap::new();
+
+        for (index, instruction) in instructions.iter().enumerate() {
+            let line = index + 1;
+
+            if matches!(
+                instruction,
+                Instruction::ChangeDirectory(_) | Instruction::List
+            ) {
+                if let Some(error) = filesystem.check_listing(listing.take(), &mut listed) {
+                    errors.push(error);
+                }
+            }
+
+            let entry = match instruction {
+                Instruction::ChangeDirectory(dir) => {
+                    let known = match dir.as_str() {
+                        "/" => true,
+                        ".." => filesystem.cwd != ROOT,
+                        name => filesystem.directories[filesystem.cwd]
+                            .children
+                            .contains_key(name),
+                    };
+
+                    if !known {
+                        errors.push(TranscriptError {
+                            line,
+                            inconsistency: match dir.as_str() {
+                                ".." => Inconsistency::AboveRoot,
+                                name => Inconsistency::UnlistedDirectory {
+                                    name: name.to_string(),
+                                    path: filesystem.path(filesystem.cwd),
+                                },
+                            },
+                        });
+                    }
+
+                    filesystem.cd(dir);
+                    continue;
+                }
+                Instruction::List => {
+                    listing = Some((line, vec![]));
+                    continue;
+                }
+                Instruction::AddDirectory(name) => {
+                    filesystem.mkdir(name);
+                    Entry::Directory(name.clone())
+                }
+                Instruction::AddFile(size, name) => {
+                    filesystem.touch(name, *size);
+                    Entry::File(name.clone(), *size)
+                }
+            };
+
+            match &mut listing {
+                // output directly follows its `$ ls`, the nth entry is on the nth line after it.
+                Some((list_line, entries)) => match entries
+                    .iter()
+                    .position(|listed| listed.name() == entry.name())
+                {
+                    Some(position) => errors.push(TranscriptError {
+                        line,
+                        inconsistency: Inconsistency::DuplicateEntry {
+                            name: entry.name().to_string(),
+                            first_line: *list_line + position + 1,
+                        },
+                    }),
+                    None => entries.push(entry),
+                },
+                None => errors.push(TranscriptError {
+                    line,
+                    inconsistency: Inconsistency::OutputWithoutList(instruction.to_string()),
+                }),
+            }
+        }
+
+        if let Some(error) = filesystem.check_listing(listing, &mut listed) {
+            errors.push(error);
+        }
+
+        if errors.is_empty() {
+            Ok(filesystem)
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Compares a finished `$ ls` of the working directory to its first listing, if any.
+    fn check_listing(
+        &self,
+        listing: Option<(usize, Vec<Entry>)>,
+        listed: &mut HashMap<DirectoryId, (usize, Vec<Entry>)>,
+    ) -> Option<TranscriptError> {
+        let (line, mut entries) = listing?;
+        entries.sort();
+
+        match listed.get(&self.cwd) {
+            Some((first_line, first)) if *first != entries => Some(TranscriptError {
+                line,
+                inconsistency: Inconsistency::ChangedListing {
+                    path: self.path(self.cwd),
+                    first_line: *first_line,
+                },
+            }),
+            Some(_) => None,
+            None => {
+                listed.insert(self.cwd, (line, entries));
+                None
+            }
+        }
+    }
+
     fn cd(&mut self, dir: &str) {
-        if dir == ".." {
-            if self.cwd == "/" {
-                return;
+        self.cwd = match dir {
+            "/" => ROOT,
+            // like a shell, `..` in the root stays there. Transcripts doing so are invalid.
+            ".." => self.directories[self.cwd].parent.unwrap_or(ROOT),
+            name => self.mkdir(name),
+        };
+    }
+
+    /// Creates the subdirectory `name` of the working directory, unless it already exists.
+    fn mkdir(&mut self, name: &str) -> DirectoryId {
+        if let Some(&id) = self.directories[self.cwd].children.get(name) {
+            return id;
+        }
+
+        let id = self.directories.len();
+        self.directories.push(Directory {
+            name: name.to_string(),
+            parent: Some(self.cwd),
+            children: BTreeMap::new(),
+            files: vec![],
+            size: 0,
+        });
+        self.directories[self.cwd]
+            .children
+            .insert(name.to_string(), id);
+
+        id
+    }
+
+    /// Adds a file to the working directory. A file listed again replaces the earlier entry.
+    fn touch(&mut self, name: &str, size: u64) {
+        let files = &mut self.directories[self.cwd].files;
+        let previous = match files.iter_mut().find(|file| file.name == name) {
+            Some(file) => std::mem::replace(&mut file.size, size),
+            None => {
+                files.push(File {
+                    name: name.to_string(),
+                    size,
+                });
+                0
             }
+        };
 
-            let mut vec = self.cwd.split('/').collect::<Vec<_>>();
+        let mut directory = Some(self.cwd);
+        while let Some(id) = directory {
+            self.directories[id].size = self.directories[id].size - previous + size;
+            directory = self.directories[id].parent;
+        }
+    }
+
+    fn size(&self, id: DirectoryId) -> u64 {
+        self.directories[id].size
+    }
+
+    fn used(&self) -> u64 {
+        self.size(ROOT)
+    }
 
-            // remove last directory, keep / at end
-            vec.remove(vec.len() - 2);
+    fn directory_sizes(&self) -> impl Iterator<Item = u64> + '_ {
+        self.directories.iter().map(|directory| directory.size)
+    }
+}
 
-            self.cwd = vec.join("/");
+// inspecting the reconstructed tree.
+impl FileSystem {
+    /// Absolute path of a directory, ending in `/`.
+    fn path(&self, id: DirectoryId) -> String {
+        let mut names = vec![];
+        let mut directory = id;
 
-            return;
+        while let Some(parent) = self.directories[directory].parent {
+            names.push(self.directories[directory].name.as_str());
+            directory = parent;
         }
 
-        if dir == "/" {
-            self.cwd = dir.to_string();
-            return;
+        names
+            .iter()
+            .rev()
+            .fold(String::from("/"), |path, name| path + name + "/")
+    }
+
+    /// Finds a directory by its absolute path, e.g. `/a/e`.
+    fn lookup(&self, path: &str) -> Option<DirectoryId> {
+        path.strip_prefix('/')?
+            .split('/')
+            .filter(|name| !name.is_empty())
+            .try_fold(ROOT, |directory, name| {
+                self.directories[directory].children.get(name).copied()
+            })
+    }
+
+    /// `id` and all directories below it, parents before their children.
+    fn descendants(&self, id: DirectoryId) -> Vec<DirectoryId> {
+        let mut result = vec![id];
+        let mut index = 0;
+
+        while let Some(&directory) = result.get(index) {
+            result.extend(self.directories[directory].children.values());
+            index += 1;
         }
 
-        self.cwd = [&self.cwd, dir, "/"].join("");
+        result
+    }
+
+    /// Indented tree of `id` with everything below it, like `tree`:
+    ///
+    /// ```text
+    /// a (92.6K)
+    /// ├── e (584B)
+    /// │   └── i (584B)
+    /// └── f (28.4K)
+    /// ```
+    fn render_tree(&self, id: DirectoryId) -> String {
+        let directory = &self.directories[id];
+        let name = if id == ROOT { "/" } else { &directory.name };
+
+        let mut output = format!("{} ({})\n", name, human_size(directory.size));
+        self.render_entries(id, "", &mut output);
+        output
     }
 
-    fn mkdir(&mut self, name: &str) {
-        let directory_path = [&self.cwd, name, "/"].join("");
+    fn render_entries(&self, id: DirectoryId, indent: &str, output: &mut String) {
+        let directory = &self.directories[id];
+
+        // directories and files, sorted by name like `tree` does.
+        let mut entries: Vec<(&str, u64, Option<DirectoryId>)> = directory
+            .children
+            .iter()
+            .map(|(name, &child)| (name.as_str(), self.size(child), Some(child)))
+            .chain(
+                directory
+                    .files
+                    .iter()
+                    .map(|file| (file.name.as_str(), file.size, None)),
+            )
+            .collect();
+        entries.sort_by_key(|&(name, _, _)| name);
+
+        for (index, (name, size, child)) in entries.iter().enumerate() {
+            let last = index + 1 == entries.len();
+            let (branch, continuation) = if last {
+                ("└── ", "    ")
+            } else {
+                ("├── ", "│   ")
+            };
 
-        if self.files.contains_key(&directory_path) {
-            return;
+            output.push_str(&format!(
+                "{}{}{} ({})\n",
+                indent,
+                branch,
+                name,
+                human_size(*size)
+            ));
+
+            if let Some(child) = child {
+                self.render_entries(*child, &(indent.to_string() + continuation), output);
+            }
         }
+    }
+
+    /// Every directory below and including `id` with its total size, largest first, like
+    /// `du -h | sort -rh`.
+    fn render_du(&self, id: DirectoryId) -> String {
+        let mut directories = self.descendants(id);
+        directories.sort_by_key(|&directory| std::cmp::Reverse(self.size(directory)));
 
-        self.files.insert(directory_path, vec![]);
+        directories
+            .into_iter()
+            .map(|directory| {
+                format!(
+                    "{}\t{}\n",
+                    human_size(self.size(directory)),
+                    self.path(directory)
+                )
+            })
+            .collect()
     }
+}
+
+/// The device a filesystem lives on.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Disk {
+    capacity: u64,
+    /// free space the update needs.
+    needed: u64,
+}
+
+/// The handheld device from the puzzle.
+const DEVICE: Disk = Disk {
+    capacity: 70_000_000,
+    needed: 30_000_000,
+};
+
+/// Search steps `cleanup` may take before it settles for the best set found so far.
+const CLEANUP_BUDGET: usize = 1_000_000;
 
-    fn touch(&mut self, filesize: i32) {
-        let directory = self.files.get_mut(self.cwd.as_str()).unwrap();
-        directory.push(File { size: filesize });
+/// Directories to delete together, see [`FileSystem::cleanup`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Cleanup {
+    directories: Vec<DirectoryId>,
+    freed: u64,
+    /// whether the search finished, otherwise this is only the best set found within budget.
+    optimal: bool,
+}
+
+// finding directories to delete.
+impl FileSystem {
+    /// How much has to be deleted before `disk` has enough free space.
+    fn space_to_free(&self, disk: Disk) -> u64 {
+        disk.needed
+            .saturating_sub(disk.capacity.saturating_sub(self.used()))
     }
 
-    fn get_dir_size(&self, dir: &str) -> i32 {
-        let mut size = 0;
+    /// The smallest single directory whose deletion frees enough space on `disk`.
+    fn smallest_to_delete(&self, disk: Disk) -> Option<DirectoryId> {
+        let to_free = self.space_to_free(disk);
 
-        for (directory, files) in &self.files {
-            if !directory.contains(dir) {
+        (0..self.directories.len())
+            .filter(|&id| self.size(id) >= to_free)
+            .min_by_key(|&id| self.size(id))
+    }
+
+    /// The `n` largest directories, largest first.
+    fn largest(&self, id: DirectoryId, n: usize) -> Vec<DirectoryId> {
+        top_k::largest(
+            self.descendants(id)
+                .into_iter()
+                .map(|directory| (self.size(directory), Reverse(directory))),
+            n,
+        )
+        .into_iter()
+        .map(|(_, Reverse(directory))| directory)
+        .collect()
+    }
+
+    /// Directories, none inside another, whose deletion frees enough space on `disk` while
+    /// deleting as little as possible. This may beat [`FileSystem::smallest_to_delete`] by
+    /// combining several smaller directories.
+    ///
+    /// Finding the best combination is a subset sum problem, so the search gives up after
+    /// `budget` steps and returns the best set found so far.
+    fn cleanup(&self, disk: Disk, budget: usize) -> Option<Cleanup> {
+        let to_free = self.space_to_free(disk);
+        if to_free == 0 {
+            return Some(Cleanup {
+                directories: vec![],
+                freed: 0,
+                optimal: true,
+            });
+        }
+
+        let single = self.smallest_to_delete(disk)?;
+        let mut search = CleanupSearch {
+            filesystem: self,
+            to_free,
+            budget,
+            // anything at least as large as the best single directory cannot improve on it.
+            candidates: (0..self.directories.len())
+                .filter(|&id| self.size(id) < self.size(single))
+                .collect(),
+            blocked: vec![0; self.directories.len()],
+            chosen: vec![],
+            best: Cleanup {
+                directories: vec![single],
+                freed: self.size(single),
+                optimal: true,
+            },
+        };
+
+        search.candidates.sort_by_key(|&id| Reverse(self.size(id)));
+        let remaining = search
+            .candidates
+            .iter()
+            .rev()
+            .scan(0, |sum, &id| {
+                *sum += self.size(id);
+                Some(*sum)
+            })
+            .collect::<Vec<_>>()
+            .into_iter()
+            .rev()
+            .collect::<Vec<_>>();
+
+        search.run(0, 0, &remaining);
+        Some(search.best)
+    }
+
+    /// Summary of the ways to free enough space on `disk`.
+    fn render_cleanup(&self, disk: Disk) -> String {
+        let mut output = format!(
+            "{} used of {}, {} needed: free {}\n",
+            human_size(self.used()),
+            human_size(disk.capacity),
+            human_size(disk.needed),
+            human_size(self.space_to_free(disk))
+        );
+
+        let Some(cleanup) = self.cleanup(disk, CLEANUP_BUDGET) else {
+            output.push_str("not possible, the update does not fit on an empty disk\n");
+            return output;
+        };
+
+        if let Some(single) = self.smallest_to_delete(disk) {
+            output.push_str(&format!(
+                "smallest directory: {}\t{}\n",
+                human_size(self.size(single)),
+                self.path(single)
+            ));
+        }
+
+        output.push_str(&format!(
+            "least space freed{}: {}\n",
+            if cleanup.optimal {
+                ""
+            } else {
+                " (search budget exhausted)"
+            },
+            human_size(cleanup.freed)
+        ));
+        for id in cleanup.directories {
+            output.push_str(&format!(
+                "  {}\t{}\n",
+                human_size(self.size(id)),
+                self.path(id)
+            ));
+        }
+
+        output
+    }
+
+    /// The directory itself, its ancestors and its descendants.
+    fn related(&self, id: DirectoryId) -> Vec<DirectoryId> {
+        let mut related = self.descendants(id);
+        let mut ancestor = self.directories[id].parent;
+
+        while let Some(directory) = ancestor {
+            related.push(directory);
+            ancestor = self.directories[directory].parent;
+        }
+
+        related
+    }
+}
+
+/// Branch and bound over the directories smaller than the best single one, largest first.
+struct CleanupSearch<'a> {
+    filesystem: &'a FileSystem,
+    to_free: u64,
+    budget: usize,
+    candidates: Vec<DirectoryId>,
+    /// how many chosen directories each directory is nested with, only `0` can be chosen.
+    blocked: Vec<usize>,
+    chosen: Vec<DirectoryId>,
+    best: Cleanup,
+}
+
+impl CleanupSearch<'_> {
+    /// `remaining[i]` is the total size of `candidates[i..]`, an upper bound of what choosing
+    /// from them can still add.
+    fn run(&mut self, start: usize, freed: u64, remaining: &[u64]) {
+        for index in start..self.candidates.len() {
+            if freed + remaining[index] < self.to_free {
+                return;
+            }
+
+            let id = self.candidates[index];
+            let size = self.filesystem.size(id);
+            if self.blocked[id] > 0 || freed + size >= self.best.freed {
+                continue;
+            }
+
+            if self.budget == 0 {
+                self.best.optimal = false;
+                return;
+            }
+            self.budget -= 1;
+
+            if freed + size >= self.to_free {
+                self.best.directories = self.chosen.iter().copied().chain([id]).collect();
+                self.best.freed = freed + size;
                 continue;
             }
 
-            size += files.iter().map(|file| file.size).sum::<i32>();
+            let related = self.filesystem.related(id);
+            for &directory in &related {
+                self.blocked[directory] += 1;
+            }
+            self.chosen.push(id);
+
+            self.run(index + 1, freed + size, remaining);
+
+            self.chosen.pop();
+            for &directory in &related {
+                self.blocked[directory] -= 1;
+            }
         }
+    }
+}
+
+/// Size with a binary unit suffix, e.g. `584B`, `92.6K` or `23.4M`.
+fn human_size(size: u64) -> String {
+    const UNITS: [char; 5] = ['B', 'K', 'M', 'G', 'T'];
+
+    let mut value = size as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit + 1 < UNITS.len() {
+        value /= 1024.0;
+        unit += 1;
+    }
 
-        size
+    if unit == 0 {
+        format!("{}B", size)
+    } else {
+        format!("{:.1}{}", value, UNITS[unit])
     }
 }
 
-#[derive(Debug)]
+/// One line of `$ ls` output.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum Entry {
+    Directory(String),
+    File(String, u64),
+}
+
+impl Entry {
+    fn name(&self) -> &str {
+        match self {
+            Entry::Directory(name) | Entry::File(name, _) => name,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Inconsistency {
+    /// `$ cd name` into a directory that was not listed in the working directory.
+    UnlistedDirectory { name: String, path: String },
+    /// `$ cd ..` in the root directory.
+    AboveRoot,
+    /// `$ ls` showed something else than the first listing of the same directory.
+    ChangedListing { path: String, first_line: usize },
+    /// `$ ls` showing the same name twice.
+    DuplicateEntry { name: String, first_line: usize },
+    /// Output that does not follow a `$ ls`.
+    OutputWithoutList(String),
+}
+
+/// A line of the transcript that contradicts what came before it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct TranscriptError {
+    line: usize,
+    inconsistency: Inconsistency,
+}
+
+impl Display for TranscriptError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: ", self.line)?;
+
+        match &self.inconsistency {
+            Inconsistency::UnlistedDirectory { name, path } => {
+                write!(
+                    f,
+                    "`$ cd {}`, but {} was never listed in {}",
+                    name, name, path
+                )
+            }
+            Inconsistency::AboveRoot => write!(f, "`$ cd ..` in the root directory"),
+            Inconsistency::ChangedListing { path, first_line } => write!(
+                f,
+                "listing of {} differs from the one on line {}",
+                path, first_line
+            ),
+            Inconsistency::DuplicateEntry { name, first_line } => {
+                write!(f, "{} was already listed on line {}", name, first_line)
+            }
+            Inconsistency::OutputWithoutList(output) => {
+                write!(f, "`{}` is not preceded by `$ ls`", output)
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
 enum Instruction {
     ChangeDirectory(String),
+    List,
     AddDirectory(String),
-    AddFile(i32, String),
-    Skip,
+    AddFile(u64, String),
 }
 
-fn parse_line(input: &str) -> IResult<&str, Instruction> {
-    let (input, line_match) = alt((tag("$ "), tag("dir "), digit1))(input)?;
-
-    let result = match line_match {
-        "$ " => {
-            if input == "ls" {
-                Instruction::Skip
-            } else {
-                let dir = input.split(' ').last().unwrap();
-                Instruction::ChangeDirectory(dir.to_string())
-            }
+impl Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Instruction::ChangeDirectory(dir) => write!(f, "$ cd {}", dir),
+            Instruction::List => write!(f, "$ ls"),
+            Instruction::AddDirectory(name) => write!(f, "dir {}", name),
+            Instruction::AddFile(size, name) => write!(f, "{} {}", size, name),
         }
-        "dir " => Instruction::AddDirectory(input.to_string()),
-        _ => Instruction::AddFile(line_match.parse::<i32>().unwrap(), input.trim().to_string()),
-    };
-    Ok((input, result))
+    }
+}
+
+fn parse_line(input: &str) -> ParseResult<'_, Instruction> {
+    alt((
+        map(preceded(token("$ cd "), not_line_ending), |dir: &str| {
+            Instruction::ChangeDirectory(dir.to_string())
+        }),
+        value(Instruction::List, token("$ ls")),
+        map(preceded(token("dir "), not_line_ending), |dir: &str| {
+            Instruction::AddDirectory(dir.to_string())
+        }),
+        map(
+            separated_pair(number, char(' '), not_line_ending),
+            |(size, name): (u64, &str)| Instruction::AddFile(size, name.to_string()),
+        ),
+    ))(input)
 }
 
-fn parse_instruction_lines(input: &str) -> (&str, Vec<Instruction>) {
-    let instructions = input
-        .lines()
-        .map(|line| -> Instruction {
-            let (_, instruction) = parse_line(line).unwrap();
+fn parse_instruction_lines(input: &str) -> Vec<Instruction> {
+    parse_input(lines(parse_line), input)
+}
 
-            instruction
-        })
-        .collect();
+fn render_errors(errors: &[TranscriptError]) -> String {
+    errors
+        .iter()
+        .map(TranscriptError::to_string)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
 
-    (input, instructions)
+/// Parses and validates the transcript. Answers computed from an inconsistent transcript would
+/// be meaningless, so this panics with every inconsistency found instead.
+fn parse_filesystem(input: &str) -> FileSystem {
+    FileSystem::from_transcript(&parse_instruction_lines(input))
+        .unwrap_or_else(|errors| panic!("invalid transcript:\n{}", render_errors(&errors)))
 }
 
 #[must_use]
-pub fn part_one(input: &str) -> Option<i32> {
-    let starting_working_directory = String::from("/");
-    let mut files = HashMap::new();
-    files.insert(starting_working_directory.clone(), vec![]);
-
-    let mut filesystem = FileSystem {
-        cwd: starting_working_directory,
-        files,
-    };
+pub fn part_one(input: &str) -> Option<u64> {
+    let filesystem = parse_filesystem(input);
 
-    let (_, lines) = parse_instruction_lines(input);
+    let total_size = filesystem
+        .directory_sizes()
+        .filter(|&size| size < 100_000)
+        .sum();
 
-    for line in lines {
-        match line {
-            Instruction::ChangeDirectory(cmd) => filesystem.cd(&cmd),
-            Instruction::AddDirectory(dir) => filesystem.mkdir(&dir),
-            Instruction::AddFile(size, _) => filesystem.touch(size),
-            Instruction::Skip => continue,
-        };
+    Some(total_size)
+}
+
+#[must_use]
+pub fn part_two(input: &str) -> Option<u64> {
+    let filesystem = parse_filesystem(input);
+
+    filesystem
+        .smallest_to_delete(DEVICE)
+        .map(|directory| filesystem.size(directory))
+}
+
+/// Nested, self-contained form of a directory, as exported to JSON:
+///
+/// ```json
+/// { "name": "e", "files": [{ "name": "i", "size": 584 }], "directories": [] }
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+struct DirectoryTree {
+    name: String,
+    #[serde(default)]
+    files: Vec<File>,
+    #[serde(default)]
+    directories: Vec<DirectoryTree>,
+}
+
+// converting from and to other representations.
+impl FileSystem {
+    fn to_tree(&self, id: DirectoryId) -> DirectoryTree {
+        let directory = &self.directories[id];
+
+        DirectoryTree {
+            name: if id == ROOT {
+                "/".to_string()
+            } else {
+                directory.name.clone()
+            },
+            files: directory.files.clone(),
+            directories: directory
+                .children
+                .values()
+                .map(|&child| self.to_tree(child))
+                .collect(),
+        }
     }
 
-    let mut total_size = 0;
-    for directory in filesystem.files.keys() {
-        let dirsize = &filesystem.get_dir_size(directory);
+    /// Builds a filesystem with the contents of `tree` in its root, whatever its name.
+    fn from_tree(tree: &DirectoryTree) -> Self {
+        let mut filesystem = FileSystem::new();
+        filesystem.insert_tree(tree);
+        filesystem.cwd = ROOT;
+        filesystem
+    }
 
-        if *dirsize < 100_000 {
-            total_size += *dirsize;
+    fn insert_tree(&mut self, tree: &DirectoryTree) {
+        for file in &tree.files {
+            self.touch(&file.name, file.size);
+        }
+
+        for directory in &tree.directories {
+            let parent = self.cwd;
+            self.cwd = self.mkdir(&directory.name);
+            self.insert_tree(directory);
+            self.cwd = parent;
         }
     }
 
-    Some(total_size)
+    fn to_json(&self, id: DirectoryId) -> String {
+        serde_json::to_string_pretty(&self.to_tree(id)).expect("trees serialize to JSON")
+    }
+
+    fn from_json(json: &str) -> serde_json::Result<Self> {
+        serde_json::from_str(json).map(|tree| FileSystem::from_tree(&tree))
+    }
+
+    /// Recreates directory `id` inside `target`, with sparse files of the recorded sizes.
+    ///
+    /// Existing directories are reused, but existing files are never touched: a file that is
+    /// already there fails with [`io::ErrorKind::AlreadyExists`].
+    fn materialize(&self, id: DirectoryId, target: &Path) -> io::Result<()> {
+        fs::create_dir_all(target)?;
+        let directory = &self.directories[id];
+
+        for file in &directory.files {
+            let path = target.join(checked_name(&file.name)?);
+            let created = OpenOptions::new().write(true).create_new(true).open(&path);
+            let created = created.map_err(|e| {
+                io::Error::new(e.kind(), format!("cannot create {}: {}", path.display(), e))
+            })?;
+            created.set_len(file.size)?;
+        }
+
+        for (name, &child) in &directory.children {
+            self.materialize(child, &target.join(checked_name(name)?))?;
+        }
+
+        Ok(())
+    }
+
+    /// Reads a directory from disk, using the apparent size of its files.
+    fn from_disk(path: &Path) -> io::Result<Self> {
+        Ok(FileSystem::from_tree(&read_tree(path)?))
+    }
 }
 
-#[must_use]
-pub fn part_two(input: &str) -> Option<i32> {
-    let starting_working_directory = String::from("/");
-    let mut files = HashMap::new();
-    files.insert(starting_working_directory.clone(), vec![]);
-
-    let mut filesystem = FileSystem {
-        cwd: starting_working_directory,
-        files,
+/// Names come from the transcript, they must not escape the directory they are created in.
+fn checked_name(name: &str) -> io::Result<&str> {
+    if name.is_empty() || name == "." || name == ".." || name.contains(['/', '\\', '\0']) {
+        Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("{:?} cannot be used as a file name", name),
+        ))
+    } else {
+        Ok(name)
+    }
+}
+
+fn read_tree(path: &Path) -> io::Result<DirectoryTree> {
+    let mut tree = DirectoryTree {
+        name: path.file_name().map_or_else(
+            || "/".to_string(),
+            |name| name.to_string_lossy().into_owned(),
+        ),
+        files: vec![],
+        directories: vec![],
     };
 
-    let (_, lines) = parse_instruction_lines(input);
+    let mut entries = fs::read_dir(path)?.collect::<io::Result<Vec<_>>>()?;
+    entries.sort_by_key(|entry| entry.file_name());
 
-    for line in lines {
-        match line {
-            Instruction::ChangeDirectory(cmd) => filesystem.cd(&cmd),
-            Instruction::AddDirectory(dir) => filesystem.mkdir(&dir),
-            Instruction::AddFile(size, _) => filesystem.touch(size),
-            Instruction::Skip => continue,
-        };
+    for entry in entries {
+        let metadata = entry.metadata()?;
+        if metadata.is_dir() {
+            tree.directories.push(read_tree(&entry.path())?);
+        } else {
+            tree.files.push(File {
+                name: entry.file_name().to_string_lossy().into_owned(),
+                size: metadata.len(),
+            });
+        }
     }
 
-    let total_space = 70_000_000;
-    let needed_for_update = 30_000_000;
-
-    let used = filesystem.get_dir_size("/");
-    let available = total_space - used;
-    let need_to_delete = needed_for_update - available;
+    Ok(tree)
+}
 
-    let mut current_dirsize = used;
+/// Alternative output instead of the answers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Report {
+    Tree,
+    Du,
+    Json,
+    Materialize,
+    Cleanup,
+    Largest,
+}
 
-    for directory in filesystem.files.keys() {
-        let &dirsize = &filesystem.get_dir_size(directory);
+impl FromStr for Report {
+    type Err = String;
 
-        if dirsize < need_to_delete || dirsize > current_dirsize {
-            continue;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "tree" => Ok(Report::Tree),
+            "du" => Ok(Report::Du),
+            "json" => Ok(Report::Json),
+            "materialize" => Ok(Report::Materialize),
+            "cleanup" => Ok(Report::Cleanup),
+            "largest" => Ok(Report::Largest),
+            _ => Err(format!(
+                "unknown report \"{}\", expected one of: tree, du, json, materialize, cleanup, largest",
+                s
+            )),
         }
+    }
+}
+
+struct Args {
+    example: bool,
+    /// read the filesystem from a JSON export instead of the transcript.
+    from_json: Option<PathBuf>,
+    /// read the filesystem from a directory on disk instead of the transcript.
+    from_disk: Option<PathBuf>,
+    /// where `materialize` creates the filesystem.
+    target: Option<PathBuf>,
+    /// disk `cleanup` frees space on.
+    disk: Disk,
+    /// how many directories `largest` lists.
+    top: usize,
+    report: Option<Report>,
+    path: Option<String>,
+}
 
-        current_dirsize = dirsize;
+fn parse_args() -> Result<Args, pico_args::Error> {
+    let mut args = pico_args::Arguments::from_env();
+    Ok(Args {
+        example: args.contains("--example"),
+        from_json: args.opt_value_from_os_str("--json", |s| Ok::<_, String>(PathBuf::from(s)))?,
+        from_disk: args.opt_value_from_os_str("--disk", |s| Ok::<_, String>(PathBuf::from(s)))?,
+        target: args.opt_value_from_os_str("--to", |s| Ok::<_, String>(PathBuf::from(s)))?,
+        disk: Disk {
+            capacity: args
+                .opt_value_from_str("--capacity")?
+                .unwrap_or(DEVICE.capacity),
+            needed: args
+                .opt_value_from_str("--needed")?
+                .unwrap_or(DEVICE.needed),
+        },
+        top: args.opt_value_from_str("--top")?.unwrap_or(10),
+        report: args.opt_free_from_str()?,
+        path: args.opt_free_from_str()?,
+    })
+}
+
+fn load_filesystem(args: &Args, folder: &str) -> Result<FileSystem, String> {
+    if let Some(file) = &args.from_json {
+        let json = fs::read_to_string(file)
+            .map_err(|e| format!("Failed to read {}: {}", file.display(), e))?;
+        return FileSystem::from_json(&json)
+            .map_err(|e| format!("Invalid JSON in {}: {}", file.display(), e));
     }
 
-    Some(current_dirsize)
+    if let Some(directory) = &args.from_disk {
+        return FileSystem::from_disk(directory)
+            .map_err(|e| format!("Failed to read {}: {}", directory.display(), e));
+    }
+
+    let input = advent_of_code::read_file(folder, 7);
+    FileSystem::from_transcript(&parse_instruction_lines(&input))
+        .map_err(|errors| format!("Invalid transcript:\n{}", render_errors(&errors)))
 }
 
 fn main() {
-    let input = &advent_of_code::read_file("inputs", 7);
-    advent_of_code::solve!(1, part_one, input);
-    advent_of_code::solve!(2, part_two, input);
+    let args = match parse_args() {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("Failed to process arguments: {}", e);
+            eprintln!(
+                "Usage: `cargo solve 07 -- [tree|du|json|materialize|cleanup|largest] [path] \
+                 [--example] [--json <file>|--disk <dir>] [--to <dir>] [--capacity <size>] \
+                 [--needed <size>] [--top <n>]`"
+            );
+            process::exit(1);
+        }
+    };
+
+    let folder = if args.example { "examples" } else { "inputs" };
+
+    let Some(report) = args.report else {
+        let input = &advent_of_code::read_file(folder, 7);
+        advent_of_code::solve!(1, part_one, input);
+        advent_of_code::solve!(2, part_two, input);
+        return;
+    };
+
+    let filesystem = match load_filesystem(&args, folder) {
+        Ok(filesystem) => filesystem,
+        Err(e) => {
+            eprintln!("{}", e);
+            process::exit(1);
+        }
+    };
+    let path = args.path.as_deref().unwrap_or("/");
+    let Some(directory) = filesystem.lookup(path) else {
+        eprintln!("No directory {} in the transcript.", path);
+        process::exit(1);
+    };
+
+    match report {
+        Report::Tree => print!("{}", filesystem.render_tree(directory)),
+        Report::Du => print!("{}", filesystem.render_du(directory)),
+        Report::Json => println!("{}", filesystem.to_json(directory)),
+        Report::Materialize => {
+            let target = args
+                .target
+                .unwrap_or_else(|| std::env::temp_dir().join(format!("aoc-07-{}", process::id())));
+
+            if let Err(e) = filesystem.materialize(directory, &target) {
+                eprintln!("Failed to materialize into {}: {}", target.display(), e);
+                process::exit(1);
+            }
+
+            println!("{}", target.display());
+        }
+        Report::Cleanup => print!("{}", filesystem.render_cleanup(args.disk)),
+        Report::Largest => {
+            for id in filesystem.largest(directory, args.top) {
+                println!(
+                    "{}\t{}",
+                    human_size(filesystem.size(id)),
+                    filesystem.path(id)
+                );
+            }
+        }
+    }
 }
 
 #[cfg(test)]
@@ -208,4 +1033,254 @@ mod tests {
         let input = advent_of_code::read_file("examples", 7);
         assert_eq!(part_two(&input), Some(24933642));
     }
+
+    #[test]
+    fn test_filesystem() {
+        let input = advent_of_code::read_file("examples", 7);
+        let filesystem = parse_filesystem(&input);
+
+        let e = filesystem.lookup("/a/e").unwrap();
+        assert_eq!(filesystem.path(e), "/a/e/");
+        assert_eq!(filesystem.size(e), 584);
+        assert_eq!(
+            filesystem.lookup("/a/").map(|a| filesystem.size(a)),
+            Some(94853)
+        );
+        assert_eq!(filesystem.lookup("/"), Some(ROOT));
+        assert_eq!(filesystem.lookup("/x"), None);
+        assert_eq!(filesystem.used(), 48381165);
+    }
+
+    #[test]
+    fn test_render_tree() {
+        let input = advent_of_code::read_file("examples", 7);
+        let filesystem = parse_filesystem(&input);
+
+        assert_eq!(
+            filesystem.render_tree(filesystem.lookup("/a").unwrap()),
+            "a (92.6K)\n\
+             ├── e (584B)\n\
+             │   └── i (584B)\n\
+             ├── f (28.4K)\n\
+             ├── g (2.5K)\n\
+             └── h.lst (61.1K)\n"
+        );
+        assert!(filesystem
+            .render_tree(ROOT)
+            .starts_with("/ (46.1M)\n├── a (92.6K)\n"));
+    }
+
+    #[test]
+    fn test_render_du() {
+        let input = advent_of_code::read_file("examples", 7);
+        let filesystem = parse_filesystem(&input);
+
+        assert_eq!(
+            filesystem.render_du(ROOT),
+            "46.1M\t/\n23.8M\t/d/\n92.6K\t/a/\n584B\t/a/e/\n"
+        );
+    }
+
+    #[test]
+    fn test_json_roundtrip() {
+        let input = advent_of_code::read_file("examples", 7);
+        let filesystem = parse_filesystem(&input);
+
+        let json = filesystem.to_json(ROOT);
+        assert!(json.contains("\"name\": \"h.lst\""));
+
+        let imported = FileSystem::from_json(&json).unwrap();
+        assert_eq!(imported.to_tree(ROOT), filesystem.to_tree(ROOT));
+        assert_eq!(imported.used(), 48381165);
+
+        let e = FileSystem::from_json(r#"{"name": "e", "files": [{"name": "i", "size": 584}]}"#);
+        assert_eq!(e.unwrap().used(), 584);
+    }
+
+    #[test]
+    fn test_materialize_roundtrip() {
+        let input = advent_of_code::read_file("examples", 7);
+        let filesystem = parse_filesystem(&input);
+
+        let target = tempfile::tempdir().unwrap();
+        filesystem.materialize(ROOT, target.path()).unwrap();
+        let measured = FileSystem::from_disk(target.path()).unwrap();
+
+        for directory in filesystem.descendants(ROOT) {
+            let path = filesystem.path(directory);
+            let on_disk = measured.lookup(&path).unwrap();
+            assert_eq!(
+                measured.size(on_disk),
+                filesystem.size(directory),
+                "{}",
+                path
+            );
+        }
+        assert_eq!(
+            fs::metadata(target.path().join("d/d.log")).unwrap().len(),
+            8033020
+        );
+    }
+
+    #[test]
+    fn test_materialize_rejects_unsafe_names() {
+        let filesystem = parse_filesystem("$ cd /\n$ ls\n1 ..");
+        let target = tempfile::tempdir().unwrap();
+
+        let error = filesystem.materialize(ROOT, target.path()).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn test_materialize_keeps_existing_files() {
+        let filesystem = parse_filesystem("$ cd /\n$ ls\n584 i\n29116 f");
+        let target = tempfile::tempdir().unwrap();
+        fs::write(target.path().join("i"), "important").unwrap();
+
+        let error = filesystem.materialize(ROOT, target.path()).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::AlreadyExists);
+        assert_eq!(
+            fs::read_to_string(target.path().join("i")).unwrap(),
+            "important"
+        );
+    }
+
+    #[test]
+    fn test_cleanup_queries() {
+        let input = advent_of_code::read_file("examples", 7);
+        let filesystem = parse_filesystem(&input);
+        let paths = |ids: Vec<DirectoryId>| -> Vec<String> {
+            ids.into_iter().map(|id| filesystem.path(id)).collect()
+        };
+
+        assert_eq!(filesystem.space_to_free(DEVICE), 8381165);
+        assert_eq!(paths(filesystem.largest(ROOT, 2)), vec!["/", "/d/"]);
+        assert_eq!(
+            paths(filesystem.largest(filesystem.lookup("/a").unwrap(), 5)),
+            vec!["/a/", "/a/e/"]
+        );
+
+        let disk = Disk {
+            capacity: 48_500_000,
+            needed: 200_000,
+        };
+        assert_eq!(filesystem.space_to_free(disk), 81165);
+        assert_eq!(
+            filesystem
+                .smallest_to_delete(disk)
+                .map(|id| filesystem.size(id)),
+            Some(94853)
+        );
+
+        // /a/ and /a/e/ are nested, so /a/ alone is the best option.
+        let cleanup = filesystem.cleanup(disk, CLEANUP_BUDGET).unwrap();
+        assert_eq!(paths(cleanup.directories), vec!["/a/"]);
+        assert!(cleanup.optimal);
+
+        let roomy = Disk {
+            capacity: 100_000_000,
+            ..DEVICE
+        };
+        assert_eq!(filesystem.cleanup(roomy, CLEANUP_BUDGET).unwrap().freed, 0);
+    }
+
+    #[test]
+    fn test_cleanup_combines_directories() {
+        let input = "$ cd /\n$ ls\ndir a\ndir b\ndir c\n\
+                     $ cd a\n$ ls\n40 x\n$ cd ..\n\
+                     $ cd b\n$ ls\n35 x\n$ cd ..\n\
+                     $ cd c\n$ ls\n100 x";
+        let filesystem = parse_filesystem(input);
+        let disk = Disk {
+            capacity: 200,
+            needed: 145,
+        };
+
+        assert_eq!(filesystem.space_to_free(disk), 120);
+        assert_eq!(filesystem.smallest_to_delete(disk), Some(ROOT));
+
+        let cleanup = filesystem.cleanup(disk, CLEANUP_BUDGET).unwrap();
+        assert_eq!(cleanup.freed, 135);
+        assert_eq!(
+            cleanup
+                .directories
+                .iter()
+                .map(|&id| filesystem.path(id))
+                .collect::<Vec<_>>(),
+            vec!["/c/", "/b/"]
+        );
+
+        let cleanup = filesystem.cleanup(disk, 1).unwrap();
+        assert!(!cleanup.optimal);
+
+        let impossible = Disk {
+            capacity: 100,
+            needed: 200,
+        };
+        assert_eq!(filesystem.cleanup(impossible, CLEANUP_BUDGET), None);
+    }
+
+    #[test]
+    fn test_human_size() {
+        assert_eq!(human_size(0), "0B");
+        assert_eq!(human_size(1023), "1023B");
+        assert_eq!(human_size(1536), "1.5K");
+        assert_eq!(human_size(24933642), "23.8M");
+    }
+
+    #[test]
+    fn test_validation() {
+        let input = "$ cd /\n$ ls\ndir a\n1 b\n$ cd x\n$ cd /\n$ cd ..\n$ cd a\n10 c\n\
+                     $ cd /\n$ ls\ndir a\n2 b";
+        let errors = FileSystem::from_transcript(&parse_instruction_lines(input)).unwrap_err();
+
+        assert_eq!(
+            render_errors(&errors),
+            "line 5: `$ cd x`, but x was never listed in /\n\
+             line 7: `$ cd ..` in the root directory\n\
+             line 9: `10 c` is not preceded by `$ ls`\n\
+             line 11: listing of / differs from the one on line 2"
+        );
+    }
+
+    #[test]
+    fn test_relisting_is_valid() {
+        let input = "$ cd /\n$ ls\ndir a\n1 b\n$ cd a\n$ ls\n$ cd ..\n$ ls\n1 b\ndir a";
+        assert!(FileSystem::from_transcript(&parse_instruction_lines(input)).is_ok());
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid transcript")]
+    fn test_refuses_invalid_transcript() {
+        let _ = part_one("$ cd /\n$ cd a\n$ ls\n1 b");
+    }
+
+    #[test]
+    fn test_nested_directories_with_same_name() {
+        let input = "$ cd /\n$ ls\ndir a\ndir b\n$ cd b\n$ ls\ndir a\n$ cd a\n$ ls\n10 x\n\
+                     $ cd /\n$ cd a\n$ ls\n5 y\n7 z";
+        let filesystem = parse_filesystem(input);
+
+        assert_eq!(
+            filesystem.lookup("/a").map(|a| filesystem.size(a)),
+            Some(12)
+        );
+        assert_eq!(
+            filesystem.lookup("/b/a").map(|a| filesystem.size(a)),
+            Some(10)
+        );
+        assert_eq!(filesystem.used(), 22);
+    }
+
+    #[test]
+    fn test_duplicate_entries() {
+        let input = "$ cd /\n$ ls\ndir a\n5 y\n7 y\n$ cd a\n$ ls\n1 x\ndir x";
+        let errors = FileSystem::from_transcript(&parse_instruction_lines(input)).unwrap_err();
+
+        assert_eq!(
+            render_errors(&errors),
+            "line 5: y was already listed on line 4\n\
+             line 9: x was already listed on line 8"
+        );
+    }
 }
@@ -3,16 +3,17 @@
  * There is no need to edit this file unless you want to change template functionality.
  */
 use std::{
-    fs::{File, OpenOptions},
+    fs::{self, OpenOptions},
     io::Write,
     process,
+    str::FromStr,
 };
 
-const MODULE_TEMPLATE: &str = r###"pub fn part_one(input: &str) -> Option<u32> {
+const MODULE_TEMPLATE: &str = r###"pub fn part_one(input: &str) -> Option<u64> {
     None
 }
 
-pub fn part_two(input: &str) -> Option<u32> {
+pub fn part_two(input: &str) -> Option<u64> {
     None
 }
 
@@ -40,75 +41,229 @@ mod tests {
 }
 "###;
 
-fn parse_args() -> Result<u8, pico_args::Error> {
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Target {
+    Module,
+    Inputs,
+    Examples,
+}
+
+impl FromStr for Target {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "module" => Ok(Target::Module),
+            "inputs" => Ok(Target::Inputs),
+            "examples" => Ok(Target::Examples),
+            _ => Err(format!(
+                "unknown target \"{}\", expected one of: module, inputs, examples",
+                s
+            )),
+        }
+    }
+}
+
+impl Target {
+    fn label(&self) -> &'static str {
+        match self {
+            Target::Module => "module",
+            Target::Inputs => "input",
+            Target::Examples => "example",
+        }
+    }
+}
+
+struct Args {
+    day: u8,
+    force: bool,
+    dry_run: bool,
+    only: Option<Target>,
+}
+
+fn parse_args() -> Result<Args, pico_args::Error> {
     let mut args = pico_args::Arguments::from_env();
-    args.free_from_str()
+    let parsed = Args {
+        force: args.contains(["-f", "--force"]),
+        dry_run: args.contains(["-n", "--dry-run"]),
+        only: args.opt_value_from_str("--only")?,
+        day: args.free_from_str()?,
+    };
+
+    // a mistyped `--dry-run` must not go unnoticed and write files.
+    let remaining = args.finish();
+    if !remaining.is_empty() {
+        let remaining: Vec<_> = remaining.iter().map(|arg| arg.to_string_lossy()).collect();
+        return Err(pico_args::Error::ArgumentParsingFailed {
+            cause: format!("unexpected arguments: {}", remaining.join(" ")),
+        });
+    }
+
+    Ok(parsed)
+}
+
+/// What scaffolding will do with a single file.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Action {
+    /// file does not exist yet.
+    Create,
+    /// file exists and `--force` was passed.
+    Overwrite,
+    /// file exists but is empty, the non-empty template is written into it.
+    Fill,
+    /// file exists but is empty like its template, there is nothing to lose by leaving it as is.
+    Keep,
+    /// file exists and has contents, needs `--force` to be touched.
+    Refuse,
+}
+
+impl Action {
+    fn plan(existing_len: Option<u64>, contents: &str, force: bool) -> Self {
+        match existing_len {
+            None => Action::Create,
+            Some(_) if force => Action::Overwrite,
+            Some(0) if contents.is_empty() => Action::Keep,
+            Some(0) => Action::Fill,
+            Some(_) => Action::Refuse,
+        }
+    }
 }
 
-fn safe_create_file(path: &str) -> Result<File, std::io::Error> {
-    OpenOptions::new().write(true).create_new(true).open(path)
+struct Step {
+    target: Target,
+    path: String,
+    contents: String,
+    action: Action,
 }
 
-fn create_file(path: &str) -> Result<File, std::io::Error> {
-    OpenOptions::new().write(true).create(true).open(path)
+fn plan_steps(args: &Args) -> Vec<Step> {
+    let day_padded = format!("{:02}", args.day);
+
+    [Target::Module, Target::Inputs, Target::Examples]
+        .into_iter()
+        .filter(|target| args.only.is_none_or(|only| only == *target))
+        .map(|target| {
+            let (path, contents) = match target {
+                Target::Module => (
+                    format!("src/bin/{}.rs", day_padded),
+                    MODULE_TEMPLATE.replace("DAY", &args.day.to_string()),
+                ),
+                Target::Inputs => (format!("src/inputs/{}.txt", day_padded), String::new()),
+                Target::Examples => (format!("src/examples/{}.txt", day_padded), String::new()),
+            };
+
+            let existing_len = fs::metadata(&path).ok().map(|meta| meta.len());
+
+            Step {
+                target,
+                action: Action::plan(existing_len, &contents, args.force),
+                path,
+                contents,
+            }
+        })
+        .collect()
+}
+
+fn print_summary(steps: &[Step], dry_run: bool) {
+    for step in steps {
+        let description = match (step.action, dry_run) {
+            (Action::Create, true) => "Would create",
+            (Action::Create, false) => "Creating",
+            (Action::Overwrite, true) => "Would overwrite",
+            (Action::Overwrite, false) => "Overwriting",
+            (Action::Fill, true) => "Would fill existing empty",
+            (Action::Fill, false) => "Filling existing empty",
+            (Action::Keep, _) => "Keeping existing empty",
+            (Action::Refuse, _) => "Refusing to overwrite non-empty",
+        };
+
+        println!(
+            "{} {} file \"{}\"",
+            description,
+            step.target.label(),
+            step.path
+        );
+    }
+}
+
+fn write_file(path: &str, contents: &str) -> Result<(), std::io::Error> {
+    let mut file = OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(path)?;
+    file.write_all(contents.as_bytes())
 }
 
 fn main() {
-    let day = match parse_args() {
-        Ok(day) => day,
-        Err(_) => {
+    let args = match parse_args() {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("Failed to process arguments: {}", e);
             eprintln!("Need to specify a day (as integer). example: `cargo scaffold 7`");
+            eprintln!("Options: --force, --dry-run, --only <module|inputs|examples>");
             process::exit(1);
         }
     };
 
-    let day_padded = format!("{:02}", day);
+    let steps = plan_steps(&args);
+    print_summary(&steps, args.dry_run);
 
-    let input_path = format!("src/inputs/{}.txt", day_padded);
-    let example_path = format!("src/examples/{}.txt", day_padded);
-    let module_path = format!("src/bin/{}.rs", day_padded);
+    if args.dry_run {
+        return;
+    }
 
-    let mut file = match safe_create_file(&module_path) {
-        Ok(file) => file,
-        Err(e) => {
-            eprintln!("Failed to create module file: {}", e);
-            process::exit(1);
-        }
-    };
+    if steps.iter().any(|step| step.action == Action::Refuse) {
+        eprintln!("---");
+        eprintln!("Nothing was written. Pass `--force` to overwrite existing files.");
+        process::exit(1);
+    }
 
-    match file.write_all(MODULE_TEMPLATE.replace("DAY", &day.to_string()).as_bytes()) {
-        Ok(_) => {
-            println!("Created module file \"{}\"", &module_path);
-        }
-        Err(e) => {
-            eprintln!("Failed to write module contents: {}", e);
+    for step in steps.iter().filter(|step| {
+        matches!(
+            step.action,
+            Action::Create | Action::Overwrite | Action::Fill
+        )
+    }) {
+        if let Err(e) = write_file(&step.path, &step.contents) {
+            eprintln!("Failed to write {} file: {}", step.target.label(), e);
             process::exit(1);
         }
     }
 
-    match create_file(&input_path) {
-        Ok(_) => {
-            println!("Created empty input file \"{}\"", &input_path);
-        }
-        Err(e) => {
-            eprintln!("Failed to create input file: {}", e);
-            process::exit(1);
-        }
+    if steps.iter().any(|step| step.target == Target::Module) {
+        println!("---");
+        println!(
+            "🎄 Type `cargo solve {:02}` to run your solution.",
+            args.day
+        );
     }
+}
 
-    match create_file(&example_path) {
-        Ok(_) => {
-            println!("Created empty example file \"{}\"", &example_path);
-        }
-        Err(e) => {
-            eprintln!("Failed to create example file: {}", e);
-            process::exit(1);
-        }
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plan_action() {
+        assert_eq!(Action::plan(None, "", false), Action::Create);
+        assert_eq!(Action::plan(None, "", true), Action::Create);
+        assert_eq!(Action::plan(Some(0), "", false), Action::Keep);
+        assert_eq!(Action::plan(Some(0), MODULE_TEMPLATE, false), Action::Fill);
+        assert_eq!(Action::plan(Some(12), "", false), Action::Refuse);
+        assert_eq!(
+            Action::plan(Some(12), MODULE_TEMPLATE, false),
+            Action::Refuse
+        );
+        assert_eq!(Action::plan(Some(0), "", true), Action::Overwrite);
+        assert_eq!(Action::plan(Some(12), "", true), Action::Overwrite);
     }
 
-    println!("---");
-    println!(
-        "🎄 Type `cargo solve {}` to run your solution.",
-        &day_padded
-    );
+    #[test]
+    fn test_parse_target() {
+        assert_eq!("module".parse::<Target>(), Ok(Target::Module));
+        assert_eq!("inputs".parse::<Target>(), Ok(Target::Inputs));
+        assert_eq!("examples".parse::<Target>(), Ok(Target::Examples));
+        assert!("input".parse::<Target>().is_err());
+    }
 }
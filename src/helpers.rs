@@ -2,3 +2,16 @@
  * Use this file if you want to extract helpers from your solutions.
  * Example import from this file: `use advent_of_code::helpers::example_fn;`.
  */
+pub mod ascii_set;
+pub mod cycle;
+pub mod diagnostic;
+pub mod grid;
+pub mod interval;
+pub mod math;
+pub mod ocr;
+pub mod parse;
+pub mod point;
+pub mod search;
+pub mod top_k;
+pub mod union_find;
+pub mod window;
@@ -0,0 +1,232 @@
+use std::fmt::{self, Debug};
+use std::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, Sub};
+use std::str::FromStr;
+
+/// A set of ASCII characters, stored as one bit per character in a `u128`.
+///
+/// Copy, allocation-free and set operations are single instructions, which makes it a good
+/// replacement for `HashSet<char>` in puzzles that intersect groups of letters.
+#[derive(Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct AsciiSet(u128);
+
+impl AsciiSet {
+    pub const EMPTY: AsciiSet = AsciiSet(0);
+
+    pub fn new() -> Self {
+        AsciiSet::EMPTY
+    }
+
+    /// Adds `c` to the set, returns whether it was newly inserted.
+    ///
+    /// Panics if `c` is not ASCII.
+    pub fn insert(&mut self, c: char) -> bool {
+        assert!(c.is_ascii(), "{:?} is not an ASCII character", c);
+        let was_present = self.contains(c);
+        self.0 |= 1 << c as u32;
+        !was_present
+    }
+
+    pub fn remove(&mut self, c: char) -> bool {
+        let was_present = self.contains(c);
+        if was_present {
+            self.0 &= !(1 << c as u32);
+        }
+        was_present
+    }
+
+    pub fn contains(&self, c: char) -> bool {
+        c.is_ascii() && self.0 & (1 << c as u32) != 0
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    pub fn union(self, other: AsciiSet) -> AsciiSet {
+        AsciiSet(self.0 | other.0)
+    }
+
+    pub fn intersection(self, other: AsciiSet) -> AsciiSet {
+        AsciiSet(self.0 & other.0)
+    }
+
+    pub fn difference(self, other: AsciiSet) -> AsciiSet {
+        AsciiSet(self.0 & !other.0)
+    }
+
+    /// The character with the lowest code point.
+    pub fn first(&self) -> Option<char> {
+        self.iter().next()
+    }
+
+    /// The characters in the set, by ascending code point.
+    pub fn iter(&self) -> Iter {
+        Iter(self.0)
+    }
+}
+
+/// Iterator over the characters of an [`AsciiSet`], see [`AsciiSet::iter`].
+#[derive(Clone, Debug)]
+pub struct Iter(u128);
+
+impl Iterator for Iter {
+    type Item = char;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.0 == 0 {
+            return None;
+        }
+
+        let code = self.0.trailing_zeros();
+        // clear the lowest set bit.
+        self.0 &= self.0 - 1;
+        char::from_u32(code)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.0.count_ones() as usize;
+        (len, Some(len))
+    }
+}
+
+impl ExactSizeIterator for Iter {}
+
+impl IntoIterator for AsciiSet {
+    type Item = char;
+    type IntoIter = Iter;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl FromStr for AsciiSet {
+    type Err = String;
+
+    /// Collects the characters of `s`, fails on the first non-ASCII one.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut set = AsciiSet::new();
+
+        for c in s.chars() {
+            if !c.is_ascii() {
+                return Err(format!("{:?} is not an ASCII character", c));
+            }
+            set.insert(c);
+        }
+
+        Ok(set)
+    }
+}
+
+impl FromIterator<char> for AsciiSet {
+    /// Panics on non-ASCII characters.
+    fn from_iter<I: IntoIterator<Item = char>>(iter: I) -> Self {
+        let mut set = AsciiSet::new();
+        for c in iter {
+            set.insert(c);
+        }
+        set
+    }
+}
+
+impl BitAnd for AsciiSet {
+    type Output = AsciiSet;
+
+    fn bitand(self, other: AsciiSet) -> AsciiSet {
+        self.intersection(other)
+    }
+}
+
+impl BitAndAssign for AsciiSet {
+    fn bitand_assign(&mut self, other: AsciiSet) {
+        *self = self.intersection(other);
+    }
+}
+
+impl BitOr for AsciiSet {
+    type Output = AsciiSet;
+
+    fn bitor(self, other: AsciiSet) -> AsciiSet {
+        self.union(other)
+    }
+}
+
+impl BitOrAssign for AsciiSet {
+    fn bitor_assign(&mut self, other: AsciiSet) {
+        *self = self.union(other);
+    }
+}
+
+impl Sub for AsciiSet {
+    type Output = AsciiSet;
+
+    fn sub(self, other: AsciiSet) -> AsciiSet {
+        self.difference(other)
+    }
+}
+
+impl Debug for AsciiSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+/// Maps `a`-`z` to 1-26 and `A`-`Z` to 27-52, `None` for anything else.
+pub fn letter_priority(c: char) -> Option<u32> {
+    match c {
+        'a'..='z' => Some(c as u32 - 'a' as u32 + 1),
+        'A'..='Z' => Some(c as u32 - 'A' as u32 + 27),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_set_operations() {
+        let a: AsciiSet = "vJrwpWtwJgWr".parse().unwrap();
+        let b: AsciiSet = "hcsFMMfFFhFp".parse().unwrap();
+
+        assert_eq!(a.len(), 8);
+        assert!(a.contains('J') && !a.contains('j'));
+        assert_eq!((a & b).iter().collect::<String>(), "p");
+        assert_eq!((a | b).len(), 14);
+        assert_eq!((a - b).len(), 7);
+        assert_eq!(a.intersection(AsciiSet::EMPTY), AsciiSet::EMPTY);
+        assert!("héllo".parse::<AsciiSet>().is_err());
+    }
+
+    #[test]
+    fn test_iteration_order() {
+        let set: AsciiSet = "zA~a\0".chars().collect();
+        assert_eq!(set.iter().collect::<String>(), "\0Aaz~");
+        assert_eq!(set.first(), Some('\0'));
+        assert_eq!(set.iter().len(), 5);
+    }
+
+    #[test]
+    fn test_insert_remove() {
+        let mut set = AsciiSet::new();
+        assert!(set.insert('x'));
+        assert!(!set.insert('x'));
+        assert!(set.remove('x'));
+        assert!(!set.remove('x'));
+        assert!(set.is_empty());
+        assert!(!set.remove('ü'));
+    }
+
+    #[test]
+    fn test_letter_priority() {
+        assert_eq!(letter_priority('a'), Some(1));
+        assert_eq!(letter_priority('z'), Some(26));
+        assert_eq!(letter_priority('A'), Some(27));
+        assert_eq!(letter_priority('Z'), Some(52));
+        assert_eq!(letter_priority('1'), None);
+    }
+}
@@ -0,0 +1,217 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// A cycle in the sequence `x0, x1 = step(x0), x2 = step(x1), ...`: from index `start` on, the
+/// sequence repeats every `length` steps.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Cycle {
+    pub start: usize,
+    pub length: usize,
+}
+
+impl Cycle {
+    /// The smallest index whose state equals the state at index `n`.
+    pub fn reduce(&self, n: u64) -> usize {
+        let (start, length) = (self.start as u64, self.length as u64);
+
+        if n < start {
+            n as usize
+        } else {
+            (start + (n - start) % length) as usize
+        }
+    }
+}
+
+/// All states up to the first repetition, together with the cycle they run into.
+#[derive(Clone, Debug)]
+pub struct History<S> {
+    /// `x0` to `x[start + length - 1]`, every distinct state exactly once.
+    pub states: Vec<S>,
+    pub cycle: Cycle,
+}
+
+impl<S> History<S> {
+    /// The state after `n` steps, for any `n`.
+    pub fn nth(&self, n: u64) -> &S {
+        &self.states[self.cycle.reduce(n)]
+    }
+}
+
+/// Runs `step` from `initial` until a state repeats, remembering every state by hash.
+///
+/// Never returns if the sequence does not cycle.
+pub fn find_cycle<S>(initial: S, step: impl FnMut(&S) -> S) -> History<S>
+where
+    S: Eq + Hash + Clone,
+{
+    find_cycle_by_key(initial, step, S::clone)
+}
+
+/// Like [`find_cycle`], but states count as equal if their `key` is. Useful when only part of
+/// the state determines its future, e.g. the top rows of a tower of falling rocks.
+pub fn find_cycle_by_key<S, K>(
+    initial: S,
+    mut step: impl FnMut(&S) -> S,
+    mut key: impl FnMut(&S) -> K,
+) -> History<S>
+where
+    K: Eq + Hash,
+{
+    let mut seen: HashMap<K, usize> = HashMap::new();
+    let mut states = vec![];
+    let mut state = initial;
+
+    loop {
+        let index = states.len();
+
+        if let Some(&start) = seen.get(&key(&state)) {
+            return History {
+                states,
+                cycle: Cycle {
+                    start,
+                    length: index - start,
+                },
+            };
+        }
+
+        seen.insert(key(&state), index);
+        let next = step(&state);
+        states.push(state);
+        state = next;
+    }
+}
+
+/// The state after `n` steps, simulating at most until the first repeated state.
+pub fn nth_state<S>(initial: S, mut step: impl FnMut(&S) -> S, n: u64) -> S
+where
+    S: Eq + Hash + Clone,
+{
+    let mut seen: HashMap<S, usize> = HashMap::new();
+    let mut states = vec![];
+    let mut state = initial;
+
+    for index in 0.. {
+        if index as u64 == n {
+            break;
+        }
+
+        if let Some(&start) = seen.get(&state) {
+            let cycle = Cycle {
+                start,
+                length: index - start,
+            };
+            return states.swap_remove(cycle.reduce(n));
+        }
+
+        seen.insert(state.clone(), index);
+        let next = step(&state);
+        states.push(state);
+        state = next;
+    }
+
+    state
+}
+
+/// Brent's cycle detection, using constant memory. Calls `step` more often than
+/// [`find_cycle`] does, prefer it when states are cheap to compute but large to store.
+///
+/// Never returns if the sequence does not cycle.
+pub fn brent<S>(initial: S, mut step: impl FnMut(&S) -> S) -> Cycle
+where
+    S: PartialEq + Clone,
+{
+    // find the cycle length by moving the tortoise to the hare at every power of two.
+    let mut power = 1;
+    let mut length = 1;
+    let mut tortoise = initial.clone();
+    let mut hare = step(&initial);
+
+    while tortoise != hare {
+        if power == length {
+            tortoise = hare.clone();
+            power *= 2;
+            length = 0;
+        }
+        hare = step(&hare);
+        length += 1;
+    }
+
+    // walk two pointers `length` apart until they meet at the start of the cycle.
+    let mut tortoise = initial.clone();
+    let mut hare = initial;
+    for _ in 0..length {
+        hare = step(&hare);
+    }
+
+    let mut start = 0;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        start += 1;
+    }
+
+    Cycle { start, length }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // x² + 1 (mod 255) runs into a cycle after a few steps, whatever the starting value.
+    fn step(x: &u64) -> u64 {
+        (x * x + 1) % 255
+    }
+
+    fn naive(n: u64) -> u64 {
+        (0..n).fold(3, |x, _| step(&x))
+    }
+
+    #[test]
+    fn test_find_cycle() {
+        let history = find_cycle(3_u64, step);
+        let Cycle { start, length } = history.cycle;
+
+        assert_eq!(history.states.len(), start + length);
+        assert_eq!(naive(start as u64), naive((start + length) as u64));
+        assert_ne!(naive(start as u64), naive((start + length - 1) as u64));
+
+        for n in 0..100 {
+            assert_eq!(*history.nth(n), naive(n));
+        }
+    }
+
+    #[test]
+    fn test_brent_matches_hashing() {
+        for initial in 0..50 {
+            assert_eq!(brent(initial, step), find_cycle(initial, step).cycle);
+        }
+    }
+
+    #[test]
+    fn test_nth_state() {
+        assert_eq!(nth_state(3_u64, step, 0), 3);
+        assert_eq!(nth_state(3_u64, step, 2), naive(2));
+
+        let cycle = brent(3_u64, step);
+        let huge = 1_000_000_000_000_u64;
+        assert_eq!(
+            nth_state(3_u64, step, huge),
+            naive(cycle.reduce(huge) as u64)
+        );
+    }
+
+    #[test]
+    fn test_find_cycle_by_key() {
+        // (value, steps taken): never repeats as a whole, but the value does.
+        let history =
+            find_cycle_by_key((0_u32, 0_u32), |&(x, n)| ((x + 1) % 3, n + 1), |&(x, _)| x);
+        assert_eq!(
+            history.cycle,
+            Cycle {
+                start: 0,
+                length: 3
+            }
+        );
+        assert_eq!(history.nth(10).0, 1);
+    }
+}
@@ -0,0 +1,174 @@
+use std::fmt::{self, Display};
+
+use nom::error::ErrorKind;
+
+/// Something a parser expected to find at the position it failed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Expected {
+    /// A literal, e.g. `" from "`.
+    Token(&'static str),
+    Char(char),
+    /// A named piece of syntax, e.g. `number`.
+    Label(&'static str),
+    /// The input was well-formed but rejected, e.g. an overflowing number.
+    Invalid(String),
+    /// A bare nom error without further information.
+    Kind(ErrorKind),
+}
+
+impl Display for Expected {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Expected::Token(token) => write!(f, "{:?}", token),
+            Expected::Char(c) => write!(f, "{:?}", c),
+            Expected::Label(label) => write!(f, "{}", label),
+            Expected::Invalid(reason) => write!(f, "{}", reason),
+            Expected::Kind(kind) => write!(f, "{}", kind_label(*kind)),
+        }
+    }
+}
+
+fn kind_label(kind: ErrorKind) -> String {
+    match kind {
+        ErrorKind::Eof => "end of input".to_string(),
+        ErrorKind::CrLf => "line break".to_string(),
+        ErrorKind::Digit => "digit".to_string(),
+        ErrorKind::Alpha => "letter".to_string(),
+        ErrorKind::AlphaNumeric => "letter or digit".to_string(),
+        ErrorKind::Space | ErrorKind::MultiSpace => "whitespace".to_string(),
+        kind => kind.description().to_lowercase(),
+    }
+}
+
+/// A parse failure, located in and rendered against the original input:
+///
+/// ```text
+/// error: expected " from "
+///  --> line 2, column 7
+///   |
+/// 2 | move 1 frm 2 to 1
+///   |       ^
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Diagnostic {
+    /// 1-based line number.
+    pub line: usize,
+    /// 1-based column, counted in characters.
+    pub column: usize,
+    /// Everything the parser would have accepted at this position.
+    pub expected: Vec<Expected>,
+    /// The full line the failure occurred on.
+    pub source_line: String,
+}
+
+impl Diagnostic {
+    /// Locates the failure at `offset` bytes into `input`.
+    pub fn new(input: &str, offset: usize, expected: Vec<Expected>) -> Self {
+        let line_start = input[..offset].rfind('\n').map_or(0, |index| index + 1);
+        let line_end = input[offset..]
+            .find('\n')
+            .map_or(input.len(), |index| offset + index);
+
+        // generic nom errors add nothing if something more specific is known. Line breaks and
+        // the end of input are specific though, they are often all that may come next.
+        let specific: Vec<Expected> = expected
+            .iter()
+            .filter(|expected| match expected {
+                Expected::Kind(kind) => matches!(kind, ErrorKind::CrLf | ErrorKind::Eof),
+                _ => true,
+            })
+            .cloned()
+            .collect();
+
+        Diagnostic {
+            line: input[..offset].matches('\n').count() + 1,
+            column: input[line_start..offset].chars().count() + 1,
+            expected: if specific.is_empty() {
+                expected
+            } else {
+                specific
+            },
+            source_line: input[line_start..line_end]
+                .trim_end_matches('\r')
+                .to_string(),
+        }
+    }
+
+    /// One-line summary, e.g. `expected one of "$ cd ", "$ ls" or number`.
+    pub fn message(&self) -> String {
+        if let Some(Expected::Invalid(reason)) = self.expected.first() {
+            return format!("invalid input: {}", reason);
+        }
+
+        let expected: Vec<String> = self.expected.iter().map(Expected::to_string).collect();
+
+        match expected.as_slice() {
+            [] => "invalid input".to_string(),
+            [single] => format!("expected {}", single),
+            [init @ .., last] => format!("expected one of {} or {}", init.join(", "), last),
+        }
+    }
+}
+
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let gutter = " ".repeat(self.line.to_string().len());
+
+        writeln!(f, "error: {}", self.message())?;
+        writeln!(
+            f,
+            "{}--> line {}, column {}",
+            gutter, self.line, self.column
+        )?;
+        writeln!(f, "{} |", gutter)?;
+        writeln!(f, "{} | {}", self.line, self.source_line)?;
+        write!(f, "{} | {}^", gutter, " ".repeat(self.column - 1))
+    }
+}
+
+impl std::error::Error for Diagnostic {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_location() {
+        let input = "move 1 from 2 to 1\nmove 1 frm 2 to 1\nmove 2 from 1 to 3";
+        let diagnostic = Diagnostic::new(input, 25, vec![Expected::Token(" from ")]);
+
+        assert_eq!(diagnostic.line, 2);
+        assert_eq!(diagnostic.column, 7);
+        assert_eq!(diagnostic.source_line, "move 1 frm 2 to 1");
+        assert_eq!(
+            diagnostic.to_string(),
+            "error: expected \" from \"\n \
+             --> line 2, column 7\n  \
+             |\n\
+             2 | move 1 frm 2 to 1\n  \
+             |       ^"
+        );
+    }
+
+    #[test]
+    fn test_message() {
+        let diagnostic = Diagnostic::new(
+            "$ foo",
+            0,
+            vec![
+                Expected::Kind(ErrorKind::Tag),
+                Expected::Token("$ cd "),
+                Expected::Token("$ ls"),
+                Expected::Label("number"),
+            ],
+        );
+        assert_eq!(
+            diagnostic.message(),
+            "expected one of \"$ cd \", \"$ ls\" or number"
+        );
+
+        let diagnostic = Diagnostic::new("1\n", 2, vec![Expected::Kind(ErrorKind::Digit)]);
+        assert_eq!((diagnostic.line, diagnostic.column), (2, 1));
+        assert_eq!(diagnostic.message(), "expected digit");
+    }
+}
@@ -0,0 +1,241 @@
+use std::fmt::{self, Display};
+
+/// A `(row, col)` position in a [`Grid`].
+pub type Pos = (usize, usize);
+
+/// `(row, col)` offsets of the four orthogonal neighbours, clockwise starting upwards.
+pub const ORTHOGONAL: [(isize, isize); 4] = [(-1, 0), (0, 1), (1, 0), (0, -1)];
+
+/// `(row, col)` offsets of all eight neighbours, clockwise starting upwards.
+pub const ADJACENT: [(isize, isize); 8] = [
+    (-1, 0),
+    (-1, 1),
+    (0, 1),
+    (1, 1),
+    (1, 0),
+    (1, -1),
+    (0, -1),
+    (-1, -1),
+];
+
+/// A rectangular 2D grid, stored row-major.
+///
+/// Thin wrapper around [`grid::Grid`] that adds position based, bounds-checked access and the
+/// neighbour / ray iteration most grid puzzles need.
+#[derive(Clone, Debug)]
+pub struct Grid<T> {
+    cells: grid::Grid<T>,
+}
+
+impl<T> Grid<T> {
+    /// Builds a grid from its rows.
+    ///
+    /// Panics if the rows do not all have the same length.
+    pub fn from_rows(rows: impl IntoIterator<Item = Vec<T>>) -> Self {
+        let mut cells = grid::Grid::from_vec(vec![], 0);
+
+        for row in rows {
+            cells.push_row(row);
+        }
+
+        Grid { cells }
+    }
+
+    /// Parses a character map, one row per line, mapping each character with `f`.
+    ///
+    /// Panics if the lines do not all have the same length.
+    pub fn parse(input: &str, mut f: impl FnMut(char) -> T) -> Self {
+        Grid::from_rows(
+            input
+                .lines()
+                .filter(|line| !line.is_empty())
+                .map(|line| line.chars().map(&mut f).collect()),
+        )
+    }
+
+    pub fn rows(&self) -> usize {
+        self.cells.rows()
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cells.cols()
+    }
+
+    pub fn contains(&self, (row, col): Pos) -> bool {
+        row < self.rows() && col < self.cols()
+    }
+
+    pub fn get(&self, (row, col): Pos) -> Option<&T> {
+        self.cells.get(row, col)
+    }
+
+    pub fn get_mut(&mut self, (row, col): Pos) -> Option<&mut T> {
+        self.cells.get_mut(row, col)
+    }
+
+    /// Moves `pos` by `(d_row, d_col)`, returns `None` if that leaves the grid.
+    pub fn offset(&self, (row, col): Pos, delta: impl Into<(isize, isize)>) -> Option<Pos> {
+        let (d_row, d_col) = delta.into();
+        let pos = (
+            row.checked_add_signed(d_row)?,
+            col.checked_add_signed(d_col)?,
+        );
+        self.contains(pos).then_some(pos)
+    }
+
+    /// All positions, row by row.
+    pub fn positions(&self) -> impl Iterator<Item = Pos> {
+        let cols = self.cols();
+        (0..self.rows()).flat_map(move |row| (0..cols).map(move |col| (row, col)))
+    }
+
+    /// All cells with their position, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = (Pos, &T)> {
+        self.positions().zip(self.cells.iter())
+    }
+
+    /// Position of the first cell matching `predicate`, row by row.
+    pub fn position(&self, mut predicate: impl FnMut(&T) -> bool) -> Option<Pos> {
+        self.iter()
+            .find(|(_, value)| predicate(value))
+            .map(|(pos, _)| pos)
+    }
+
+    /// Cells of a row, from left to right.
+    pub fn row(&self, row: usize) -> impl DoubleEndedIterator<Item = &T> {
+        self.cells.iter_row(row)
+    }
+
+    /// Cells of a column, from top to bottom.
+    pub fn col(&self, col: usize) -> impl DoubleEndedIterator<Item = &T> {
+        self.cells.iter_col(col)
+    }
+
+    /// In-bounds neighbours of `pos` for each of the given offsets.
+    pub fn neighbours<'a>(
+        &'a self,
+        pos: Pos,
+        offsets: &'a [(isize, isize)],
+    ) -> impl Iterator<Item = (Pos, &'a T)> + 'a {
+        offsets
+            .iter()
+            .filter_map(move |&delta| self.offset(pos, delta))
+            .map(move |pos| (pos, &self[pos]))
+    }
+
+    /// In-bounds orthogonal neighbours of `pos`.
+    pub fn neighbours4(&self, pos: Pos) -> impl Iterator<Item = (Pos, &T)> {
+        self.neighbours(pos, &ORTHOGONAL)
+    }
+
+    /// In-bounds orthogonal and diagonal neighbours of `pos`.
+    pub fn neighbours8(&self, pos: Pos) -> impl Iterator<Item = (Pos, &T)> {
+        self.neighbours(pos, &ADJACENT)
+    }
+
+    /// Walks from `pos` in steps of `delta` until the edge of the grid. `pos` itself is not
+    /// included.
+    pub fn ray(
+        &self,
+        pos: Pos,
+        delta: impl Into<(isize, isize)>,
+    ) -> impl Iterator<Item = (Pos, &T)> {
+        let delta = delta.into();
+        std::iter::successors(self.offset(pos, delta), move |&pos| self.offset(pos, delta))
+            .map(move |pos| (pos, &self[pos]))
+    }
+
+    /// Renders the grid with one character per cell.
+    pub fn render(&self, mut f: impl FnMut(&T) -> char) -> String {
+        let mut output = String::with_capacity(self.rows() * (self.cols() + 1));
+
+        for row in 0..self.rows() {
+            output.extend(self.row(row).map(&mut f));
+            output.push('\n');
+        }
+
+        output
+    }
+}
+
+impl<T> std::ops::Index<Pos> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, pos: Pos) -> &Self::Output {
+        self.get(pos).expect("position out of bounds")
+    }
+}
+
+impl<T> std::ops::IndexMut<Pos> for Grid<T> {
+    fn index_mut(&mut self, pos: Pos) -> &mut Self::Output {
+        self.get_mut(pos).expect("position out of bounds")
+    }
+}
+
+impl<T: Display> Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in 0..self.rows() {
+            for value in self.row(row) {
+                write!(f, "{}", value)?;
+            }
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn example() -> Grid<char> {
+        Grid::parse("abc\ndef\nghi\n", |c| c)
+    }
+
+    #[test]
+    fn test_parse() {
+        let grid = Grid::parse("123\n456\n", |c| c.to_digit(10).unwrap());
+        assert_eq!(grid.rows(), 2);
+        assert_eq!(grid.cols(), 3);
+        assert_eq!(grid.get((1, 2)), Some(&6));
+        assert_eq!(grid.get((2, 0)), None);
+        assert_eq!(grid.position(|&v| v == 5), Some((1, 1)));
+    }
+
+    #[test]
+    fn test_neighbours() {
+        let grid = example();
+
+        let corner: Vec<char> = grid.neighbours4((0, 0)).map(|(_, &c)| c).collect();
+        assert_eq!(corner, vec!['b', 'd']);
+
+        let center: String = grid.neighbours8((1, 1)).map(|(_, &c)| c).collect();
+        assert_eq!(center, "bcfihgda");
+    }
+
+    #[test]
+    fn test_ray() {
+        let grid = example();
+
+        let right: String = grid.ray((1, 0), (0, 1)).map(|(_, &c)| c).collect();
+        assert_eq!(right, "ef");
+
+        let diagonal: Vec<Pos> = grid.ray((2, 2), (-1, -1)).map(|(pos, _)| pos).collect();
+        assert_eq!(diagonal, vec![(1, 1), (0, 0)]);
+
+        assert_eq!(grid.ray((0, 0), (-1, 0)).count(), 0);
+    }
+
+    #[test]
+    fn test_views() {
+        let grid = example();
+        assert_eq!(grid.row(1).collect::<String>(), "def");
+        assert_eq!(grid.col(2).rev().collect::<String>(), "ifc");
+        assert_eq!(grid.to_string(), "abc\ndef\nghi\n");
+        assert_eq!(
+            grid.render(|&c| if c == 'e' { '#' } else { '.' }),
+            "...\n.#.\n...\n"
+        );
+    }
+}
@@ -0,0 +1,306 @@
+use std::fmt::{self, Display};
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+
+/// A closed interval of integers, `start` and `end` included.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Interval {
+    pub start: i64,
+    pub end: i64,
+}
+
+impl Interval {
+    /// Panics if `start > end`.
+    pub fn new(start: i64, end: i64) -> Self {
+        assert!(
+            start <= end,
+            "interval start {} is after end {}",
+            start,
+            end
+        );
+        Interval { start, end }
+    }
+
+    /// Number of integers in the interval. A `u128`, as `i64::MIN..=i64::MAX` holds 2^64 of them.
+    pub fn size(&self) -> u128 {
+        u128::from(self.start.abs_diff(self.end)) + 1
+    }
+
+    pub fn contains(&self, value: i64) -> bool {
+        self.start <= value && value <= self.end
+    }
+
+    /// Whether `other` lies completely within this interval.
+    pub fn contains_interval(&self, other: &Interval) -> bool {
+        self.start <= other.start && other.end <= self.end
+    }
+
+    pub fn overlaps(&self, other: &Interval) -> bool {
+        self.start <= other.end && other.start <= self.end
+    }
+
+    pub fn intersection(&self, other: &Interval) -> Option<Interval> {
+        self.overlaps(other)
+            .then(|| Interval::new(self.start.max(other.start), self.end.min(other.end)))
+    }
+
+    /// Whether both intervals can be merged into one, i.e. they overlap or are adjacent.
+    fn touches(&self, other: &Interval) -> bool {
+        self.start <= other.end.saturating_add(1) && other.start <= self.end.saturating_add(1)
+    }
+}
+
+impl From<RangeInclusive<i64>> for Interval {
+    fn from(range: RangeInclusive<i64>) -> Self {
+        Interval::new(*range.start(), *range.end())
+    }
+}
+
+impl FromStr for Interval {
+    type Err = String;
+
+    /// Parses `start-end`, e.g. `2-4` or `-3--1`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // the separator is the first `-` after the sign of `start`.
+        let (start, end) = s
+            .char_indices()
+            .skip(1)
+            .find(|&(_, c)| c == '-')
+            .map(|(index, _)| (&s[..index], &s[index + 1..]))
+            .ok_or_else(|| format!("expected \"start-end\", got \"{}\"", s))?;
+        let start = start.parse::<i64>().map_err(|e| e.to_string())?;
+        let end = end.parse::<i64>().map_err(|e| e.to_string())?;
+
+        if start > end {
+            return Err(format!("interval start {} is after end {}", start, end));
+        }
+
+        Ok(Interval { start, end })
+    }
+}
+
+impl Display for Interval {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}-{}", self.start, self.end)
+    }
+}
+
+/// A set of integers, stored as sorted and disjoint intervals. Overlapping or adjacent
+/// intervals are merged on insertion.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct IntervalSet {
+    intervals: Vec<Interval>,
+}
+
+impl IntervalSet {
+    pub fn new() -> Self {
+        IntervalSet::default()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    /// Total number of integers covered by the set, at most 2^64 as the intervals are disjoint.
+    pub fn len(&self) -> u128 {
+        self.intervals.iter().map(Interval::size).sum()
+    }
+
+    /// The merged intervals, in ascending order.
+    pub fn iter(&self) -> impl Iterator<Item = &Interval> {
+        self.intervals.iter()
+    }
+
+    pub fn insert(&mut self, interval: Interval) {
+        // first interval that could be merged with or comes after the new one.
+        let from = self
+            .intervals
+            .partition_point(|existing| existing.end.saturating_add(1) < interval.start);
+        // first interval after `from` that neither overlaps nor is adjacent.
+        let to = from
+            + self.intervals[from..]
+                .iter()
+                .take_while(|existing| existing.touches(&interval))
+                .count();
+
+        let merged = self.intervals[from..to]
+            .iter()
+            .fold(interval, |merged, existing| Interval {
+                start: merged.start.min(existing.start),
+                end: merged.end.max(existing.end),
+            });
+
+        self.intervals.splice(from..to, [merged]);
+    }
+
+    pub fn contains(&self, value: i64) -> bool {
+        let index = self
+            .intervals
+            .partition_point(|interval| interval.end < value);
+        self.intervals
+            .get(index)
+            .is_some_and(|interval| interval.contains(value))
+    }
+
+    /// Whether every integer of `interval` is in the set.
+    pub fn contains_interval(&self, interval: &Interval) -> bool {
+        let index = self
+            .intervals
+            .partition_point(|existing| existing.end < interval.start);
+        self.intervals
+            .get(index)
+            .is_some_and(|existing| existing.contains_interval(interval))
+    }
+
+    pub fn union(&self, other: &IntervalSet) -> IntervalSet {
+        let mut union = self.clone();
+        for &interval in other.iter() {
+            union.insert(interval);
+        }
+        union
+    }
+
+    pub fn intersection(&self, other: &IntervalSet) -> IntervalSet {
+        let mut intersection = IntervalSet::new();
+        let (mut a, mut b) = (0, 0);
+
+        while a < self.intervals.len() && b < other.intervals.len() {
+            let (left, right) = (self.intervals[a], other.intervals[b]);
+
+            if let Some(overlap) = left.intersection(&right) {
+                intersection.intervals.push(overlap);
+            }
+
+            if left.end < right.end {
+                a += 1;
+            } else {
+                b += 1;
+            }
+        }
+
+        intersection
+    }
+
+    /// All integers of this set that are not in `other`.
+    pub fn difference(&self, other: &IntervalSet) -> IntervalSet {
+        let mut difference = IntervalSet::new();
+        let mut b = 0;
+
+        for &interval in self.iter() {
+            // first integer of `interval` not yet handled, `None` once it is fully covered.
+            let mut rest = Some(interval.start);
+
+            while b < other.intervals.len() && other.intervals[b].end < interval.start {
+                b += 1;
+            }
+
+            for cut in other.intervals[b..]
+                .iter()
+                .take_while(|cut| cut.start <= interval.end)
+            {
+                let Some(start) = rest else {
+                    break;
+                };
+
+                if cut.start > start {
+                    difference
+                        .intervals
+                        .push(Interval::new(start, cut.start - 1));
+                }
+
+                rest = cut
+                    .end
+                    .checked_add(1)
+                    .filter(|&next| next <= interval.end)
+                    .map(|next| next.max(start));
+            }
+
+            if let Some(start) = rest {
+                difference
+                    .intervals
+                    .push(Interval::new(start, interval.end));
+            }
+        }
+
+        difference
+    }
+}
+
+impl FromIterator<Interval> for IntervalSet {
+    fn from_iter<I: IntoIterator<Item = Interval>>(iter: I) -> Self {
+        let mut set = IntervalSet::new();
+        for interval in iter {
+            set.insert(interval);
+        }
+        set
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(intervals: &[(i64, i64)]) -> IntervalSet {
+        intervals
+            .iter()
+            .map(|&(start, end)| Interval::new(start, end))
+            .collect()
+    }
+
+    #[test]
+    fn test_interval() {
+        let a: Interval = "2-8".parse().unwrap();
+        let b = Interval::from(3..=7);
+
+        assert_eq!(a.size(), 7);
+        assert!(a.contains_interval(&b));
+        assert!(!b.contains_interval(&a));
+        assert!(a.overlaps(&Interval::new(8, 9)));
+        assert!(!a.overlaps(&Interval::new(9, 9)));
+        assert_eq!(
+            a.intersection(&Interval::new(6, 12)),
+            Some(Interval::new(6, 8))
+        );
+        assert!("8-2".parse::<Interval>().is_err());
+        assert!("8".parse::<Interval>().is_err());
+        assert!("-8".parse::<Interval>().is_err());
+        assert_eq!("-3-5".parse(), Ok(Interval::new(-3, 5)));
+        assert_eq!("-3--1".parse(), Ok(Interval::new(-3, -1)));
+        assert!("-1--3".parse::<Interval>().is_err());
+    }
+
+    #[test]
+    fn test_insert_merges() {
+        let merged = set(&[(10, 12), (1, 3), (5, 6), (4, 4), (20, 25), (11, 21)]);
+        assert_eq!(merged, set(&[(1, 6), (10, 25)]));
+        assert_eq!(merged.len(), 22);
+        assert!(merged.contains(4));
+        assert!(!merged.contains(7));
+        assert!(merged.contains_interval(&Interval::new(12, 18)));
+        assert!(!merged.contains_interval(&Interval::new(5, 10)));
+    }
+
+    #[test]
+    fn test_set_operations() {
+        let a = set(&[(1, 10), (20, 30)]);
+        let b = set(&[(5, 22), (28, 40)]);
+
+        assert_eq!(a.union(&b), set(&[(1, 40)]));
+        assert_eq!(a.intersection(&b), set(&[(5, 10), (20, 22), (28, 30)]));
+        assert_eq!(a.difference(&b), set(&[(1, 4), (23, 27)]));
+        assert_eq!(b.difference(&a), set(&[(11, 19), (31, 40)]));
+        assert_eq!(a.difference(&a), IntervalSet::new());
+        assert_eq!(a.difference(&IntervalSet::new()), a);
+        assert_eq!(
+            set(&[(0, i64::MAX)]).difference(&set(&[(5, i64::MAX)])),
+            set(&[(0, 4)])
+        );
+    }
+
+    #[test]
+    fn test_full_range_size() {
+        let full = Interval::new(i64::MIN, i64::MAX);
+        assert_eq!(full.size(), 1 << 64);
+        assert_eq!(set(&[(i64::MIN, -1), (0, i64::MAX)]).len(), 1 << 64);
+    }
+}
@@ -0,0 +1,223 @@
+/// Greatest common divisor, `gcd(0, 0) == 0`.
+pub fn gcd(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+/// Least common multiple, `lcm(0, n) == 0`.
+///
+/// Panics if the result does not fit into a `u64`.
+pub fn lcm(a: u64, b: u64) -> u64 {
+    if a == 0 || b == 0 {
+        return 0;
+    }
+
+    (a / gcd(a, b))
+        .checked_mul(b)
+        .unwrap_or_else(|| panic!("lcm({}, {}) overflows u64", a, b))
+}
+
+/// Greatest common divisor of all numbers, `0` for none.
+pub fn gcd_all(numbers: impl IntoIterator<Item = u64>) -> u64 {
+    numbers.into_iter().fold(0, gcd)
+}
+
+/// Least common multiple of all numbers, `1` for none. E.g. the first time a set of independent
+/// cycles all line up again.
+pub fn lcm_all(numbers: impl IntoIterator<Item = u64>) -> u64 {
+    numbers.into_iter().fold(1, lcm)
+}
+
+/// Extended Euclidean algorithm: returns `(g, x, y)` with `g = gcd(a, b) >= 0` and
+/// `a * x + b * y == g`.
+///
+/// The only gcd that does not fit into an `i64` is 2^63, of `a` and `b` that are each zero or
+/// `i64::MIN` (but not both zero). `g` wraps around to `i64::MIN` then.
+pub fn extended_gcd(a: i64, b: i64) -> (i64, i64, i64) {
+    let (g, x, y) = extended_gcd_i128(i128::from(a), i128::from(b));
+    // |x| <= max(|b| / g, 1) and |y| <= max(|a| / g, 1) always fit, only g itself can overflow:
+    // it is 2^63 for (i64::MIN, i64::MIN), (i64::MIN, 0) and (0, i64::MIN).
+    (g as i64, x as i64, y as i64)
+}
+
+fn extended_gcd_i128(a: i128, b: i128) -> (i128, i128, i128) {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_x, mut x) = (1, 0);
+    let (mut old_y, mut y) = (0, 1);
+
+    while r != 0 {
+        let quotient = old_r / r;
+        (old_r, r) = (r, old_r - quotient * r);
+        (old_x, x) = (x, old_x - quotient * x);
+        (old_y, y) = (y, old_y - quotient * y);
+    }
+
+    if old_r < 0 {
+        (-old_r, -old_x, -old_y)
+    } else {
+        (old_r, old_x, old_y)
+    }
+}
+
+/// `base ^ exp mod modulus`, by repeated squaring.
+///
+/// Panics if `modulus` is zero.
+pub fn mod_pow(base: u64, mut exp: u64, modulus: u64) -> u64 {
+    assert_ne!(modulus, 0, "modulus must not be zero");
+
+    let modulus = u128::from(modulus);
+    let mut base = u128::from(base) % modulus;
+    let mut result = 1 % modulus;
+
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = result * base % modulus;
+        }
+        base = base * base % modulus;
+        exp >>= 1;
+    }
+
+    result as u64
+}
+
+/// `x` in `0..modulus` with `a * x ≡ 1 (mod modulus)`, `None` if `a` and `modulus` are not
+/// coprime.
+///
+/// Panics if `modulus` is zero.
+pub fn mod_inverse(a: i64, modulus: u64) -> Option<u64> {
+    assert_ne!(modulus, 0, "modulus must not be zero");
+
+    let modulus = i128::from(modulus);
+    let (g, x, _) = extended_gcd_i128(i128::from(a).rem_euclid(modulus), modulus);
+
+    (g == 1).then(|| x.rem_euclid(modulus) as u64)
+}
+
+/// Solves a system of congruences `x ≡ residue (mod modulus)` with the Chinese Remainder
+/// Theorem. The moduli do not need to be pairwise coprime.
+///
+/// Returns the smallest non-negative solution and the modulus it repeats with (the lcm of all
+/// moduli), or `None` if the congruences contradict each other.
+///
+/// Panics if a modulus is zero or the combined modulus does not fit into a `u64`.
+pub fn crt(congruences: impl IntoIterator<Item = (i64, u64)>) -> Option<(u64, u64)> {
+    let mut solution: i128 = 0;
+    let mut combined: i128 = 1;
+
+    for (residue, modulus) in congruences {
+        assert_ne!(modulus, 0, "modulus must not be zero");
+        let modulus = i128::from(modulus);
+        let residue = i128::from(residue).rem_euclid(modulus);
+
+        // solution + combined * k ≡ residue (mod modulus)  <=>  combined * k ≡ diff (mod modulus)
+        let (g, inverse, _) = extended_gcd_i128(combined, modulus);
+        let diff = residue - solution;
+        if diff % g != 0 {
+            return None;
+        }
+
+        let step = modulus / g;
+        let next_combined = u64::try_from(combined)
+            .ok()
+            .and_then(|combined| combined.checked_mul(step as u64))
+            .expect("combined modulus overflows u64");
+
+        // both factors are below `step` <= u64::MAX, so their product fits into an u128.
+        let k = ((diff / g).rem_euclid(step) as u128 * inverse.rem_euclid(step) as u128
+            % step as u128) as i128;
+
+        solution += combined * k;
+        combined = i128::from(next_combined);
+    }
+
+    Some((solution as u64, combined as u64))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gcd_lcm() {
+        assert_eq!(gcd(12, 18), 6);
+        assert_eq!(gcd(0, 7), 7);
+        assert_eq!(gcd(0, 0), 0);
+        assert_eq!(lcm(4, 6), 12);
+        assert_eq!(lcm(0, 6), 0);
+        assert_eq!(gcd_all([12, 18, 27]), 3);
+        assert_eq!(gcd_all([]), 0);
+        assert_eq!(lcm_all([2, 3, 4, 5]), 60);
+        assert_eq!(lcm_all([]), 1);
+        assert_eq!(lcm_all([19, 23, 13, 17]), 96577);
+    }
+
+    #[test]
+    #[should_panic(expected = "overflows u64")]
+    fn test_lcm_overflow() {
+        lcm(u64::MAX, u64::MAX - 1);
+    }
+
+    #[test]
+    fn test_extended_gcd() {
+        for (a, b) in [
+            (240, 46),
+            (-240, 46),
+            (17, 0),
+            (0, -5),
+            (i64::MAX, i64::MAX - 1),
+        ] {
+            let (g, x, y) = extended_gcd(a, b);
+            assert!(g >= 0);
+            assert_eq!(
+                i128::from(a) * i128::from(x) + i128::from(b) * i128::from(y),
+                i128::from(g)
+            );
+        }
+        assert_eq!(extended_gcd(240, 46).0, 2);
+        assert_eq!(extended_gcd(i64::MIN, 0).0, i64::MIN);
+    }
+
+    #[test]
+    fn test_mod_pow() {
+        assert_eq!(mod_pow(4, 13, 497), 445);
+        assert_eq!(mod_pow(2, 0, 1), 0);
+        assert_eq!(mod_pow(u64::MAX, 2, u64::MAX - 1), 1);
+        assert_eq!(mod_pow(20201227 - 1, 20201227 - 1, 20201227), 1);
+    }
+
+    #[test]
+    fn test_mod_inverse() {
+        assert_eq!(mod_inverse(3, 11), Some(4));
+        assert_eq!(mod_inverse(-3, 11), Some(7));
+        assert_eq!(mod_inverse(6, 9), None);
+        assert_eq!(mod_inverse(1, 1), Some(0));
+    }
+
+    #[test]
+    #[should_panic(expected = "modulus must not be zero")]
+    fn test_mod_inverse_zero_modulus() {
+        mod_inverse(3, 0);
+    }
+
+    #[test]
+    fn test_crt() {
+        assert_eq!(crt([(2, 3), (3, 5), (2, 7)]), Some((23, 105)));
+        assert_eq!(crt([(-1, 4), (1, 6)]), Some((7, 12)));
+        assert_eq!(crt([(1, 4), (2, 6)]), None);
+        assert_eq!(crt([]), Some((0, 1)));
+
+        // bus schedule style: 7,13,x,x,59,x,31,19 departing at t + index.
+        let buses = [(7, 0), (13, 1), (59, 4), (31, 6), (19, 7)];
+        let congruences = buses.map(|(id, offset): (i64, i64)| (-offset, id as u64));
+        assert_eq!(crt(congruences), Some((1068781, 7 * 13 * 59 * 31 * 19)));
+
+        // large, pairwise coprime moduli, whose products only fit into 128 bits.
+        let p = 4_294_967_291_u64;
+        let q = 4_294_967_279_u64;
+        let (x, m) = crt([(5, p), (7, q)]).unwrap();
+        assert_eq!(m, p * q);
+        assert_eq!((x % p, x % q), (5, 7));
+    }
+}
@@ -0,0 +1,227 @@
+use super::grid::Grid;
+
+/// Block letter fonts the puzzles draw their answers in.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Font {
+    /// 4 pixels wide (`I` 3, `Y` 5) and 6 high, e.g. a CRT screen.
+    Small,
+    /// 6 pixels wide and 10 high, e.g. a message in the sky.
+    Large,
+}
+
+impl Font {
+    pub fn height(self) -> usize {
+        match self {
+            Font::Small => 6,
+            Font::Large => 10,
+        }
+    }
+
+    fn from_height(height: usize) -> Option<Self> {
+        [Font::Small, Font::Large]
+            .into_iter()
+            .find(|font| font.height() == height)
+    }
+
+    /// Known letters, each cropped to its lit pixels.
+    fn glyphs(self) -> &'static [(char, &'static [&'static str])] {
+        match self {
+            Font::Small => SMALL,
+            Font::Large => LARGE,
+        }
+    }
+}
+
+#[rustfmt::skip]
+const SMALL: &[(char, &[&str])] = &[
+    ('A', &[".##.", "#..#", "#..#", "####", "#..#", "#..#"]),
+    ('B', &["###.", "#..#", "###.", "#..#", "#..#", "###."]),
+    ('C', &[".##.", "#..#", "#...", "#...", "#..#", ".##."]),
+    ('E', &["####", "#...", "###.", "#...", "#...", "####"]),
+    ('F', &["####", "#...", "###.", "#...", "#...", "#..."]),
+    ('G', &[".##.", "#..#", "#...", "#.##", "#..#", ".###"]),
+    ('H', &["#..#", "#..#", "####", "#..#", "#..#", "#..#"]),
+    ('I', &["###", ".#.", ".#.", ".#.", ".#.", "###"]),
+    ('J', &["..##", "...#", "...#", "...#", "#..#", ".##."]),
+    ('K', &["#..#", "#.#.", "##..", "#.#.", "#.#.", "#..#"]),
+    ('L', &["#...", "#...", "#...", "#...", "#...", "####"]),
+    ('O', &[".##.", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('P', &["###.", "#..#", "#..#", "###.", "#...", "#..."]),
+    ('R', &["###.", "#..#", "#..#", "###.", "#.#.", "#..#"]),
+    ('S', &[".###", "#...", "#...", ".##.", "...#", "###."]),
+    ('U', &["#..#", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('Y', &["#...#", "#...#", ".#.#.", "..#..", "..#..", "..#.."]),
+    ('Z', &["####", "...#", "..#.", ".#..", "#...", "####"]),
+];
+
+#[rustfmt::skip]
+const LARGE: &[(char, &[&str])] = &[
+    ('A', &["..##..", ".#..#.", "#....#", "#....#", "#....#", "######", "#....#", "#....#", "#....#", "#....#"]),
+    ('B', &["#####.", "#....#", "#....#", "#....#", "#####.", "#....#", "#....#", "#....#", "#....#", "#####."]),
+    ('C', &[".####.", "#....#", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#....#", ".####."]),
+    ('E', &["######", "#.....", "#.....", "#.....", "#####.", "#.....", "#.....", "#.....", "#.....", "######"]),
+    ('F', &["######", "#.....", "#.....", "#.....", "#####.", "#.....", "#.....", "#.....", "#.....", "#....."]),
+    ('G', &[".####.", "#....#", "#.....", "#.....", "#.....", "#..###", "#....#", "#....#", "#...##", ".###.#"]),
+    ('H', &["#....#", "#....#", "#....#", "#....#", "######", "#....#", "#....#", "#....#", "#....#", "#....#"]),
+    ('J', &["...###", "....#.", "....#.", "....#.", "....#.", "....#.", "....#.", "#...#.", "#...#.", ".###.."]),
+    ('K', &["#....#", "#...#.", "#..#..", "#.#...", "##....", "##....", "#.#...", "#..#..", "#...#.", "#....#"]),
+    ('L', &["#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "######"]),
+    ('N', &["#....#", "##...#", "##...#", "#.#..#", "#.#..#", "#..#.#", "#..#.#", "#...##", "#...##", "#....#"]),
+    ('P', &["#####.", "#....#", "#....#", "#....#", "#####.", "#.....", "#.....", "#.....", "#.....", "#....."]),
+    ('R', &["#####.", "#....#", "#....#", "#....#", "#####.", "#..#..", "#...#.", "#...#.", "#....#", "#....#"]),
+    ('X', &["#....#", "#....#", ".#..#.", ".#..#.", "..##..", "..##..", ".#..#.", ".#..#.", "#....#", "#....#"]),
+    ('Z', &["######", ".....#", ".....#", "....#.", "...#..", "..#...", ".#....", "#.....", "#.....", "######"]),
+];
+
+/// Reads the block letters drawn by the lit pixels of `pixels`.
+///
+/// Blank rows around the text are ignored, the font is picked by the height of what remains.
+/// Letters are separated by blank columns, so any spacing between them works.
+pub fn recognize(pixels: &Grid<bool>) -> Result<String, String> {
+    let lit_rows: Vec<usize> = (0..pixels.rows())
+        .filter(|&row| pixels.row(row).any(|&lit| lit))
+        .collect();
+    let (Some(&top), Some(&bottom)) = (lit_rows.first(), lit_rows.last()) else {
+        return Err("no lit pixels".to_string());
+    };
+
+    let height = bottom - top + 1;
+    let font =
+        Font::from_height(height).ok_or_else(|| format!("no font is {} pixels high", height))?;
+
+    let is_blank = |col: usize| (top..=bottom).all(|row| !pixels[(row, col)]);
+
+    let mut text = String::new();
+    let mut col = 0;
+
+    while col < pixels.cols() {
+        if is_blank(col) {
+            col += 1;
+            continue;
+        }
+
+        let start = col;
+        while col < pixels.cols() && !is_blank(col) {
+            col += 1;
+        }
+
+        let matches = |glyph: &[&str]| {
+            glyph.iter().enumerate().all(|(row, line)| {
+                line.len() == col - start
+                    && line
+                        .chars()
+                        .enumerate()
+                        .all(|(offset, c)| (c == '#') == pixels[(top + row, start + offset)])
+            })
+        };
+
+        let letter = font
+            .glyphs()
+            .iter()
+            .find(|(_, glyph)| matches(glyph))
+            .map(|&(letter, _)| letter)
+            .ok_or_else(|| format!("unknown letter at column {}", start + 1))?;
+
+        text.push(letter);
+    }
+
+    Ok(text)
+}
+
+/// Like [`recognize`], for text drawn with `#` (lit) and `.` or spaces. Lines may have
+/// different lengths, e.g. when trailing spaces were trimmed.
+pub fn recognize_str(image: &str) -> Result<String, String> {
+    let lines: Vec<&str> = image.lines().collect();
+    let width = lines
+        .iter()
+        .map(|line| line.chars().count())
+        .max()
+        .unwrap_or(0);
+
+    let mut rows = Vec::with_capacity(lines.len());
+    for line in lines {
+        let mut row = Vec::with_capacity(width);
+        for c in line.chars() {
+            match c {
+                '#' => row.push(true),
+                '.' | ' ' => row.push(false),
+                c => return Err(format!("unexpected character {:?}", c)),
+            }
+        }
+        row.resize(width, false);
+        rows.push(row);
+    }
+
+    recognize(&Grid::from_rows(rows))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_small_font() {
+        let image = "\
+###..####.####.#..#.####.####.#..#..##..
+#..#.#....#....#.#..#....#....#..#.#..#.
+#..#.###..###..##...###..###..####.#..#.
+###..#....#....#.#..#....#....#..#.####.
+#.#..#....#....#.#..#....#....#..#.#..#.
+#..#.####.####.#..#.####.#....#..#.#..#.";
+
+        assert_eq!(recognize_str(image), Ok("REEKEFHA".to_string()));
+    }
+
+    #[test]
+    fn test_large_font() {
+        let image = "\
+#....#..#....#..######
+#....#..#....#.......#
+.#..#...#....#.......#
+.#..#...#....#......#.
+..##....######.....#..
+..##....#....#....#...
+.#..#...#....#...#....
+.#..#...#....#..#.....
+#....#..#....#..#.....
+#....#..#....#..######";
+
+        assert_eq!(recognize_str(image), Ok("XHZ".to_string()));
+    }
+
+    #[test]
+    fn test_every_letter() {
+        for font in [Font::Small, Font::Large] {
+            let glyphs = font.glyphs();
+            let image: Vec<String> = (0..font.height())
+                .map(|row| {
+                    glyphs
+                        .iter()
+                        .map(|(_, glyph)| glyph[row])
+                        .collect::<Vec<_>>()
+                        .join("..")
+                })
+                .collect();
+            let expected: String = glyphs.iter().map(|&(letter, _)| letter).collect();
+
+            assert_eq!(recognize_str(&image.join("\n")), Ok(expected));
+        }
+    }
+
+    #[test]
+    fn test_padding_and_errors() {
+        let image = "\n  ##\n #  #\n #  #\n ####\n #  #\n #  #\n\n";
+        assert_eq!(recognize_str(image), Ok("A".to_string()));
+
+        assert_eq!(
+            recognize_str("####\n####\n####\n####\n####\n####").unwrap_err(),
+            "unknown letter at column 1"
+        );
+        assert_eq!(
+            recognize_str("#\n#").unwrap_err(),
+            "no font is 2 pixels high"
+        );
+        assert_eq!(recognize_str("..").unwrap_err(), "no lit pixels");
+        assert!(recognize_str("#x").is_err());
+    }
+}
@@ -0,0 +1,363 @@
+use std::cell::RefCell;
+use std::cmp::Ordering;
+use std::fmt::Display;
+use std::str::FromStr;
+
+use nom::character::complete::{char, digit1, line_ending, multispace0, satisfy, space0};
+use nom::combinator::{all_consuming, map, map_res, opt, recognize};
+use nom::error::{ErrorKind, FromExternalError};
+use nom::multi::{many1, separated_list1};
+use nom::sequence::{pair, separated_pair, terminated, tuple};
+use nom::{Finish, IResult, Parser};
+
+use super::diagnostic::{Diagnostic, Expected};
+use super::grid::Grid;
+use super::interval::Interval;
+
+/// nom error type of the parsers in this module: where parsing failed and what would have been
+/// accepted there.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Error<'a> {
+    pub input: &'a str,
+    pub expected: Vec<Expected>,
+}
+
+pub type ParseResult<'a, O> = IResult<&'a str, O, Error<'a>>;
+
+impl<'a> Error<'a> {
+    pub fn expected(input: &'a str, expected: Expected) -> Self {
+        track(input, &expected);
+        Error {
+            input,
+            expected: vec![expected],
+        }
+    }
+
+    fn is_invalid(&self) -> bool {
+        self.expected
+            .iter()
+            .any(|expected| matches!(expected, Expected::Invalid(_)))
+    }
+}
+
+impl<'a> nom::error::ParseError<&'a str> for Error<'a> {
+    fn from_error_kind(input: &'a str, kind: ErrorKind) -> Self {
+        Error::expected(input, Expected::Kind(kind))
+    }
+
+    fn append(_input: &'a str, _kind: ErrorKind, other: Self) -> Self {
+        other
+    }
+
+    fn from_char(input: &'a str, c: char) -> Self {
+        Error::expected(input, Expected::Char(c))
+    }
+
+    fn or(mut self, other: Self) -> Self {
+        // keep whichever alternative got further, merge them if they failed at the same spot.
+        match self.input.len().cmp(&other.input.len()) {
+            Ordering::Less => self,
+            Ordering::Greater => other,
+            Ordering::Equal => {
+                merge(&mut self.expected, other.expected);
+                self
+            }
+        }
+    }
+}
+
+impl<'a, E: Display> FromExternalError<&'a str, E> for Error<'a> {
+    fn from_external_error(input: &'a str, _kind: ErrorKind, e: E) -> Self {
+        Error::expected(input, Expected::Invalid(e.to_string()))
+    }
+}
+
+fn merge(expected: &mut Vec<Expected>, other: impl IntoIterator<Item = Expected>) {
+    for item in other {
+        if !expected.contains(&item) {
+            expected.push(item);
+        }
+    }
+}
+
+/// The furthest position any parser failed at during the current [`finish`] call.
+///
+/// Combinators like `separated_list1` or `alt` backtrack and discard the errors of the branches
+/// they gave up on, so the error that reaches [`finish`] is often a bland "expected end of
+/// input" at the start of a malformed line. The deepest failure is usually the one to report.
+#[derive(Clone, Default)]
+struct Tracker {
+    /// Address range of the input given to [`finish`].
+    range: (usize, usize),
+    furthest: Option<(usize, Vec<Expected>)>,
+}
+
+thread_local! {
+    static TRACKER: RefCell<Option<Tracker>> = const { RefCell::new(None) };
+}
+
+fn track(input: &str, expected: &Expected) {
+    let address = input.as_ptr() as usize;
+
+    TRACKER.with(|tracker| {
+        let mut tracker = tracker.borrow_mut();
+        let Some(tracker) = tracker.as_mut() else {
+            return;
+        };
+
+        if address < tracker.range.0 || address > tracker.range.1 {
+            return;
+        }
+
+        match &mut tracker.furthest {
+            Some((furthest, list)) if *furthest == address => {
+                merge(list, [expected.clone()]);
+            }
+            Some((furthest, _)) if *furthest > address => {}
+            furthest => *furthest = Some((address, vec![expected.clone()])),
+        }
+    });
+}
+
+/// Runs `parser` on `input`, which must be consumed completely (up to trailing whitespace).
+pub fn finish<'a, O>(
+    parser: impl Parser<&'a str, O, Error<'a>>,
+    input: &'a str,
+) -> Result<O, Diagnostic> {
+    let start = input.as_ptr() as usize;
+    let previous = TRACKER.with(|tracker| {
+        tracker.replace(Some(Tracker {
+            range: (start, start + input.len()),
+            furthest: None,
+        }))
+    });
+
+    let result = all_consuming(terminated(parser, multispace0))(input).finish();
+    let tracker = TRACKER.with(|tracker| tracker.replace(previous)).unwrap();
+
+    result.map(|(_, output)| output).map_err(|error| {
+        let offset = input.len() - error.input.len();
+
+        match tracker.furthest {
+            // semantic errors (e.g. an overflowing number) are reported where they happened.
+            Some((address, expected)) if !error.is_invalid() && address - start >= offset => {
+                Diagnostic::new(input, address - start, expected)
+            }
+            _ => Diagnostic::new(input, offset, error.expected),
+        }
+    })
+}
+
+/// Like [`finish`], but panics with the rendered [`Diagnostic`]. Meant for solutions, which
+/// cannot do anything useful with a malformed input.
+pub fn parse_input<'a, O>(parser: impl Parser<&'a str, O, Error<'a>>, input: &'a str) -> O {
+    finish(parser, input).unwrap_or_else(|diagnostic| panic!("{}", diagnostic))
+}
+
+/// Reports failures of `parser` at the very start of its input as `expected <name>`, instead of
+/// whatever its first sub-parser expected.
+pub fn label<'a, O>(
+    name: &'static str,
+    mut parser: impl Parser<&'a str, O, Error<'a>>,
+) -> impl FnMut(&'a str) -> ParseResult<'a, O> {
+    move |input: &'a str| {
+        let snapshot = TRACKER.with(|tracker| tracker.borrow().clone());
+
+        match parser.parse(input) {
+            Err(nom::Err::Error(error))
+                if error.input.len() == input.len() && !error.is_invalid() =>
+            {
+                TRACKER.with(|tracker| *tracker.borrow_mut() = snapshot);
+                Err(nom::Err::Error(Error::expected(
+                    input,
+                    Expected::Label(name),
+                )))
+            }
+            result => result,
+        }
+    }
+}
+
+/// Matches the literal `token`, like nom's `tag`, but reports it as expected on failure.
+pub fn token<'a>(token: &'static str) -> impl FnMut(&'a str) -> ParseResult<'a, &'a str> {
+    move |input: &'a str| match input.strip_prefix(token) {
+        Some(rest) => Ok((rest, &input[..token.len()])),
+        None => Err(nom::Err::Error(Error::expected(
+            input,
+            Expected::Token(token),
+        ))),
+    }
+}
+
+/// A signed or unsigned integer of any type that can be parsed from a string.
+pub fn number<T: FromStr>(input: &str) -> ParseResult<'_, T>
+where
+    T::Err: Display,
+{
+    label(
+        "number",
+        map_res(recognize(pair(opt(char('-')), digit1)), str::parse),
+    )(input)
+}
+
+/// Numbers separated by `separator` and optional spaces around it, e.g. `1, 2,3`.
+pub fn numbers<'a, T: FromStr>(separator: char) -> impl FnMut(&'a str) -> ParseResult<'a, Vec<T>>
+where
+    T::Err: Display,
+{
+    separated_list1(tuple((space0, char(separator), space0)), number)
+}
+
+/// One or more lines, each parsed with `parser`.
+pub fn lines<'a, O>(
+    parser: impl Parser<&'a str, O, Error<'a>>,
+) -> impl FnMut(&'a str) -> ParseResult<'a, Vec<O>> {
+    separated_list1(line_ending, parser)
+}
+
+/// One or more blocks separated by a blank line, each parsed with `parser`.
+pub fn blocks<'a, O>(
+    parser: impl Parser<&'a str, O, Error<'a>>,
+) -> impl FnMut(&'a str) -> ParseResult<'a, Vec<O>> {
+    separated_list1(pair(line_ending, line_ending), parser)
+}
+
+/// `key<separator>value`, e.g. `Sensor: 12` with `separator = ": "`.
+pub fn key_value<'a, K, V>(
+    key: impl Parser<&'a str, K, Error<'a>>,
+    separator: &'static str,
+    value: impl Parser<&'a str, V, Error<'a>>,
+) -> impl FnMut(&'a str) -> ParseResult<'a, (K, V)> {
+    separated_pair(key, token(separator), value)
+}
+
+/// An inclusive range written as `start-end`, e.g. `2-4`.
+pub fn interval(input: &str) -> ParseResult<'_, Interval> {
+    map_res(separated_pair(number, char('-'), number), |(start, end)| {
+        if start <= end {
+            Ok(Interval::new(start, end))
+        } else {
+            Err(format!("interval start {} is after end {}", start, end))
+        }
+    })(input)
+}
+
+/// A rectangular block of single digits, e.g. a height map.
+pub fn digit_grid(input: &str) -> ParseResult<'_, Grid<u32>> {
+    let digit = label(
+        "digit",
+        map(satisfy(|c| c.is_ascii_digit()), |c| c.to_digit(10).unwrap()),
+    );
+
+    map_res(lines(many1(digit)), |rows| {
+        if rows.iter().all(|row| row.len() == rows[0].len()) {
+            Ok(Grid::from_rows(rows))
+        } else {
+            Err("rows have different lengths")
+        }
+    })(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nom::branch::alt;
+    use nom::character::complete::alpha1;
+
+    #[test]
+    fn test_numbers() {
+        assert_eq!(finish(numbers::<i64>(','), "1, -2,3\n"), Ok(vec![1, -2, 3]));
+        assert_eq!(finish(lines(number::<u8>), "1\n2\n3"), Ok(vec![1, 2, 3]));
+        assert_eq!(
+            finish(number::<u8>, "256").unwrap_err().message(),
+            "invalid input: number too large to fit in target type"
+        );
+    }
+
+    #[test]
+    fn test_blocks() {
+        assert_eq!(
+            finish(blocks(lines(number::<u32>)), "1000\n2000\n\n4000\n"),
+            Ok(vec![vec![1000, 2000], vec![4000]])
+        );
+    }
+
+    #[test]
+    fn test_key_value() {
+        assert_eq!(
+            finish(
+                lines(key_value(alpha1, ": ", number::<i32>)),
+                "a: 1\nbc: -2"
+            ),
+            Ok(vec![("a", 1), ("bc", -2)])
+        );
+    }
+
+    #[test]
+    fn test_interval() {
+        assert_eq!(finish(interval, "2-4"), Ok(Interval::new(2, 4)));
+        assert!(finish(interval, "4-2").is_err());
+    }
+
+    #[test]
+    fn test_digit_grid() {
+        let grid = finish(digit_grid, "123\n456\n").unwrap();
+        assert_eq!(grid.get((1, 0)), Some(&4));
+        assert_eq!(grid.cols(), 3);
+
+        assert_eq!(
+            finish(digit_grid, "123\n45\n").unwrap_err().message(),
+            "invalid input: rows have different lengths"
+        );
+    }
+
+    #[test]
+    fn test_reports_furthest_failure() {
+        let instruction = tuple((
+            token("move "),
+            number::<u32>,
+            token(" from "),
+            number::<u32>,
+        ));
+        let diagnostic = finish(lines(instruction), "move 1 from 2\nmove 1 frm 2").unwrap_err();
+
+        assert_eq!((diagnostic.line, diagnostic.column), (2, 7));
+        assert_eq!(diagnostic.expected, vec![Expected::Token(" from ")]);
+        assert_eq!(diagnostic.source_line, "move 1 frm 2");
+
+        // only a line break or the end of the input may follow a complete number.
+        let diagnostic = finish(blocks(lines(number::<u32>)), "1000\n2000\n\n3000x").unwrap_err();
+        assert_eq!((diagnostic.line, diagnostic.column), (4, 5));
+        assert_eq!(
+            diagnostic.message(),
+            "expected one of line break or end of input"
+        );
+    }
+
+    #[test]
+    fn test_merges_alternatives() {
+        let line = alt((
+            token("$ cd "),
+            token("$ ls"),
+            token("dir "),
+            recognize(number::<u32>),
+        ));
+        let diagnostic = finish(lines(line), "$ ls\n$ foo").unwrap_err();
+
+        assert_eq!((diagnostic.line, diagnostic.column), (2, 1));
+        assert_eq!(
+            diagnostic.message(),
+            "expected one of \"$ cd \", \"$ ls\", \"dir \", number or end of input"
+        );
+    }
+
+    #[test]
+    fn test_trailing_input() {
+        let diagnostic = finish(lines(number::<u32>), "1\n2\n3x").unwrap_err();
+        assert_eq!((diagnostic.line, diagnostic.column), (3, 2));
+        assert_eq!(
+            diagnostic.message(),
+            "expected one of line break or end of input"
+        );
+    }
+}
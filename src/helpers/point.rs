@@ -0,0 +1,331 @@
+use std::fmt::{self, Display};
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+use std::str::FromStr;
+
+use super::grid::Pos;
+
+/// A point or vector on the integer plane. Follows screen coordinates like [`super::grid::Grid`]:
+/// `x` grows to the right, `y` grows downwards.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Point2 {
+    pub x: i64,
+    pub y: i64,
+}
+
+impl Point2 {
+    pub const ORIGIN: Point2 = Point2 { x: 0, y: 0 };
+
+    pub const fn new(x: i64, y: i64) -> Self {
+        Point2 { x, y }
+    }
+
+    pub fn manhattan(&self, other: &Point2) -> u64 {
+        self.x.abs_diff(other.x) + self.y.abs_diff(other.y)
+    }
+
+    pub fn chebyshev(&self, other: &Point2) -> u64 {
+        self.x.abs_diff(other.x).max(self.y.abs_diff(other.y))
+    }
+
+    /// Componentwise sign, useful to step towards another point one tile at a time.
+    pub fn signum(&self) -> Point2 {
+        Point2::new(self.x.signum(), self.y.signum())
+    }
+
+    /// Rotates by 90° clockwise around the origin, as seen on screen.
+    pub fn rotate_right(&self) -> Point2 {
+        Point2::new(-self.y, self.x)
+    }
+
+    /// Rotates by 90° counter-clockwise around the origin, as seen on screen.
+    pub fn rotate_left(&self) -> Point2 {
+        Point2::new(self.y, -self.x)
+    }
+
+    /// Grid position of the point, `None` if it has a negative coordinate.
+    pub fn to_pos(&self) -> Option<Pos> {
+        Some((usize::try_from(self.y).ok()?, usize::try_from(self.x).ok()?))
+    }
+}
+
+impl From<Pos> for Point2 {
+    fn from((row, col): Pos) -> Self {
+        Point2::new(col as i64, row as i64)
+    }
+}
+
+impl From<Direction> for Point2 {
+    fn from(direction: Direction) -> Self {
+        direction.delta()
+    }
+}
+
+impl Display for Point2 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({}, {})", self.x, self.y)
+    }
+}
+
+/// A point or vector in integer space.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Point3 {
+    pub x: i64,
+    pub y: i64,
+    pub z: i64,
+}
+
+impl Point3 {
+    pub const ORIGIN: Point3 = Point3 { x: 0, y: 0, z: 0 };
+
+    pub const fn new(x: i64, y: i64, z: i64) -> Self {
+        Point3 { x, y, z }
+    }
+
+    pub fn manhattan(&self, other: &Point3) -> u64 {
+        self.x.abs_diff(other.x) + self.y.abs_diff(other.y) + self.z.abs_diff(other.z)
+    }
+
+    pub fn chebyshev(&self, other: &Point3) -> u64 {
+        self.x
+            .abs_diff(other.x)
+            .max(self.y.abs_diff(other.y))
+            .max(self.z.abs_diff(other.z))
+    }
+
+    pub fn signum(&self) -> Point3 {
+        Point3::new(self.x.signum(), self.y.signum(), self.z.signum())
+    }
+
+    /// Quarter turn around the x axis, counter-clockwise when looking from positive x.
+    pub fn rotate_x(&self) -> Point3 {
+        Point3::new(self.x, -self.z, self.y)
+    }
+
+    /// Quarter turn around the y axis, counter-clockwise when looking from positive y.
+    pub fn rotate_y(&self) -> Point3 {
+        Point3::new(self.z, self.y, -self.x)
+    }
+
+    /// Quarter turn around the z axis, counter-clockwise when looking from positive z.
+    pub fn rotate_z(&self) -> Point3 {
+        Point3::new(-self.y, self.x, self.z)
+    }
+}
+
+impl Display for Point3 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({}, {}, {})", self.x, self.y, self.z)
+    }
+}
+
+macro_rules! impl_point_ops {
+    ($point:ident { $($field:ident),+ }) => {
+        impl Add for $point {
+            type Output = $point;
+
+            fn add(self, other: $point) -> $point {
+                $point { $($field: self.$field + other.$field),+ }
+            }
+        }
+
+        impl Sub for $point {
+            type Output = $point;
+
+            fn sub(self, other: $point) -> $point {
+                $point { $($field: self.$field - other.$field),+ }
+            }
+        }
+
+        impl Neg for $point {
+            type Output = $point;
+
+            fn neg(self) -> $point {
+                $point { $($field: -self.$field),+ }
+            }
+        }
+
+        impl Mul<i64> for $point {
+            type Output = $point;
+
+            fn mul(self, factor: i64) -> $point {
+                $point { $($field: self.$field * factor),+ }
+            }
+        }
+
+        impl AddAssign for $point {
+            fn add_assign(&mut self, other: $point) {
+                *self = *self + other;
+            }
+        }
+
+        impl SubAssign for $point {
+            fn sub_assign(&mut self, other: $point) {
+                *self = *self - other;
+            }
+        }
+    };
+}
+
+impl_point_ops!(Point2 { x, y });
+impl_point_ops!(Point3 { x, y, z });
+
+/// One of the eight compass directions, in screen coordinates (`Up` is towards row 0).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Direction {
+    Up,
+    UpRight,
+    Right,
+    DownRight,
+    Down,
+    DownLeft,
+    Left,
+    UpLeft,
+}
+
+impl Direction {
+    /// The four orthogonal directions, clockwise starting upwards.
+    pub const ORTHOGONAL: [Direction; 4] = [
+        Direction::Up,
+        Direction::Right,
+        Direction::Down,
+        Direction::Left,
+    ];
+
+    /// All eight directions, clockwise starting upwards.
+    pub const ALL: [Direction; 8] = [
+        Direction::Up,
+        Direction::UpRight,
+        Direction::Right,
+        Direction::DownRight,
+        Direction::Down,
+        Direction::DownLeft,
+        Direction::Left,
+        Direction::UpLeft,
+    ];
+
+    fn index(&self) -> usize {
+        *self as usize
+    }
+
+    /// Unit step in this direction. Diagonals move on both axes.
+    pub fn delta(&self) -> Point2 {
+        let (d_row, d_col) = self.offset();
+        Point2::new(d_col as i64, d_row as i64)
+    }
+
+    /// `(row, col)` offset of this direction, as used by [`super::grid::Grid`].
+    pub fn offset(&self) -> (isize, isize) {
+        super::grid::ADJACENT[self.index()]
+    }
+
+    pub fn is_diagonal(&self) -> bool {
+        self.index() % 2 == 1
+    }
+
+    /// Turns by 90° clockwise.
+    pub fn turn_right(&self) -> Direction {
+        Direction::ALL[(self.index() + 2) % 8]
+    }
+
+    /// Turns by 90° counter-clockwise.
+    pub fn turn_left(&self) -> Direction {
+        Direction::ALL[(self.index() + 6) % 8]
+    }
+
+    pub fn opposite(&self) -> Direction {
+        Direction::ALL[(self.index() + 4) % 8]
+    }
+}
+
+impl From<Direction> for (isize, isize) {
+    fn from(direction: Direction) -> Self {
+        direction.offset()
+    }
+}
+
+impl TryFrom<char> for Direction {
+    type Error = String;
+
+    /// Parses the orthogonal directions from `U`/`D`/`L`/`R`, `^`/`v`/`<`/`>` or `N`/`S`/`W`/`E`.
+    fn try_from(c: char) -> Result<Self, Self::Error> {
+        match c {
+            'U' | '^' | 'N' => Ok(Direction::Up),
+            'D' | 'v' | 'S' => Ok(Direction::Down),
+            'L' | '<' | 'W' => Ok(Direction::Left),
+            'R' | '>' | 'E' => Ok(Direction::Right),
+            _ => Err(format!("unknown direction \"{}\"", c)),
+        }
+    }
+}
+
+impl FromStr for Direction {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => Direction::try_from(c),
+            _ => Err(format!("unknown direction \"{}\"", s)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_point2() {
+        let a = Point2::new(1, -2);
+        let b = Point2::new(-3, 4);
+
+        assert_eq!(a + b, Point2::new(-2, 2));
+        assert_eq!(a - b, Point2::new(4, -6));
+        assert_eq!(-a * 2, Point2::new(-2, 4));
+        assert_eq!(a.manhattan(&b), 10);
+        assert_eq!(a.chebyshev(&b), 6);
+        assert_eq!((b - a).signum(), Point2::new(-1, 1));
+
+        let mut c = a;
+        c += b;
+        c -= a;
+        assert_eq!(c, b);
+    }
+
+    #[test]
+    fn test_rotation() {
+        let up = Direction::Up.delta();
+        assert_eq!(up.rotate_right(), Direction::Right.delta());
+        assert_eq!(up.rotate_left(), Direction::Left.delta());
+        assert_eq!(up.rotate_right().rotate_right(), Direction::Down.delta());
+
+        let p = Point3::new(1, 2, 3);
+        assert_eq!(p.rotate_z(), Point3::new(-2, 1, 3));
+        assert_eq!(p.rotate_x().rotate_x().rotate_x().rotate_x(), p);
+        assert_eq!(p.rotate_y().manhattan(&Point3::ORIGIN), 6);
+        assert_eq!(p.chebyshev(&Point3::ORIGIN), 3);
+    }
+
+    #[test]
+    fn test_direction() {
+        assert_eq!("U".parse(), Ok(Direction::Up));
+        assert_eq!(Direction::try_from('v'), Ok(Direction::Down));
+        assert_eq!(Direction::try_from('<'), Ok(Direction::Left));
+        assert!("UR".parse::<Direction>().is_err());
+        assert!(Direction::try_from('x').is_err());
+
+        assert_eq!(Direction::Up.turn_right(), Direction::Right);
+        assert_eq!(Direction::Up.turn_left(), Direction::Left);
+        assert_eq!(Direction::UpLeft.opposite(), Direction::DownRight);
+        assert!(Direction::DownLeft.is_diagonal());
+        assert_eq!(Direction::DownLeft.delta(), Point2::new(-1, 1));
+        assert_eq!(Direction::DownLeft.offset(), (1, -1));
+    }
+
+    #[test]
+    fn test_grid_conversion() {
+        assert_eq!(Point2::from((2, 5)), Point2::new(5, 2));
+        assert_eq!(Point2::new(5, 2).to_pos(), Some((2, 5)));
+        assert_eq!(Point2::new(-1, 2).to_pos(), None);
+    }
+}
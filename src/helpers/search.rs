@@ -0,0 +1,329 @@
+use std::cmp::Ordering;
+use std::collections::hash_map::Entry;
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+use std::hash::Hash;
+use std::ops::Add;
+
+use super::grid::{Grid, Pos};
+
+/// Distances from a start node and the tree of shortest paths found by [`bfs`] or [`dijkstra`].
+#[derive(Clone, Debug)]
+pub struct Search<N, C> {
+    start: N,
+    distances: HashMap<N, C>,
+    parents: HashMap<N, N>,
+}
+
+impl<N: Eq + Hash + Clone, C: Copy> Search<N, C> {
+    pub fn start(&self) -> &N {
+        &self.start
+    }
+
+    /// Distance from the start to `node`, `None` if it is unreachable.
+    pub fn distance(&self, node: &N) -> Option<C> {
+        self.distances.get(node).copied()
+    }
+
+    /// All reachable nodes with their distance, in no particular order.
+    pub fn distances(&self) -> impl Iterator<Item = (&N, C)> {
+        self.distances
+            .iter()
+            .map(|(node, &distance)| (node, distance))
+    }
+
+    /// Shortest path from the start to `node`, both included.
+    pub fn path(&self, node: &N) -> Option<Vec<N>> {
+        if !self.distances.contains_key(node) {
+            return None;
+        }
+
+        let mut path = vec![node.clone()];
+        while let Some(parent) = self.parents.get(path.last().unwrap()) {
+            path.push(parent.clone());
+        }
+        path.reverse();
+
+        Some(path)
+    }
+}
+
+/// Breadth-first search from `start`, every edge has a length of one.
+pub fn bfs<N, I>(start: N, mut neighbours: impl FnMut(&N) -> I) -> Search<N, usize>
+where
+    N: Eq + Hash + Clone,
+    I: IntoIterator<Item = N>,
+{
+    let mut distances = HashMap::from([(start.clone(), 0)]);
+    let mut parents = HashMap::new();
+    let mut queue = VecDeque::from([start.clone()]);
+
+    while let Some(node) = queue.pop_front() {
+        let distance = distances[&node];
+
+        for next in neighbours(&node) {
+            if let Entry::Vacant(entry) = distances.entry(next.clone()) {
+                entry.insert(distance + 1);
+                parents.insert(next.clone(), node.clone());
+                queue.push_back(next);
+            }
+        }
+    }
+
+    Search {
+        start,
+        distances,
+        parents,
+    }
+}
+
+/// Heap entry ordered by `priority` only, smallest first.
+struct Candidate<N, C> {
+    priority: C,
+    cost: C,
+    node: N,
+}
+
+impl<N, C: Ord> PartialEq for Candidate<N, C> {
+    fn eq(&self, other: &Self) -> bool {
+        self.priority == other.priority
+    }
+}
+
+impl<N, C: Ord> Eq for Candidate<N, C> {}
+
+impl<N, C: Ord> PartialOrd for Candidate<N, C> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<N, C: Ord> Ord for Candidate<N, C> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.priority.cmp(&self.priority)
+    }
+}
+
+/// Dijkstra's algorithm from `start`. `neighbours` yields each reachable node with the
+/// (non-negative) cost of the edge leading to it.
+pub fn dijkstra<N, C, I>(start: N, mut neighbours: impl FnMut(&N) -> I) -> Search<N, C>
+where
+    N: Eq + Hash + Clone,
+    C: Copy + Ord + Add<Output = C> + Default,
+    I: IntoIterator<Item = (N, C)>,
+{
+    let mut distances = HashMap::from([(start.clone(), C::default())]);
+    let mut parents = HashMap::new();
+    let mut heap = BinaryHeap::from([Candidate {
+        priority: C::default(),
+        cost: C::default(),
+        node: start.clone(),
+    }]);
+
+    while let Some(Candidate { cost, node, .. }) = heap.pop() {
+        if cost > distances[&node] {
+            continue;
+        }
+
+        for (next, edge) in neighbours(&node) {
+            let next_cost = cost + edge;
+            if distances
+                .get(&next)
+                .is_some_and(|&known| known <= next_cost)
+            {
+                continue;
+            }
+
+            distances.insert(next.clone(), next_cost);
+            parents.insert(next.clone(), node.clone());
+            heap.push(Candidate {
+                priority: next_cost,
+                cost: next_cost,
+                node: next,
+            });
+        }
+    }
+
+    Search {
+        start,
+        distances,
+        parents,
+    }
+}
+
+/// A* search from `start` to the first node matching `is_goal`. `heuristic` must never
+/// overestimate the remaining cost. Returns the total cost and the path, both ends included.
+pub fn astar<N, C, I>(
+    start: N,
+    mut neighbours: impl FnMut(&N) -> I,
+    mut heuristic: impl FnMut(&N) -> C,
+    mut is_goal: impl FnMut(&N) -> bool,
+) -> Option<(C, Vec<N>)>
+where
+    N: Eq + Hash + Clone,
+    C: Copy + Ord + Add<Output = C> + Default,
+    I: IntoIterator<Item = (N, C)>,
+{
+    let mut distances = HashMap::from([(start.clone(), C::default())]);
+    let mut parents: HashMap<N, N> = HashMap::new();
+    let mut heap = BinaryHeap::from([Candidate {
+        priority: heuristic(&start),
+        cost: C::default(),
+        node: start.clone(),
+    }]);
+
+    while let Some(Candidate { cost, node, .. }) = heap.pop() {
+        if cost > distances[&node] {
+            continue;
+        }
+
+        if is_goal(&node) {
+            let search = Search {
+                start,
+                distances,
+                parents,
+            };
+            return search.path(&node).map(|path| (cost, path));
+        }
+
+        for (next, edge) in neighbours(&node) {
+            let next_cost = cost + edge;
+            if distances
+                .get(&next)
+                .is_some_and(|&known| known <= next_cost)
+            {
+                continue;
+            }
+
+            distances.insert(next.clone(), next_cost);
+            parents.insert(next.clone(), node.clone());
+            heap.push(Candidate {
+                priority: next_cost + heuristic(&next),
+                cost: next_cost,
+                node: next,
+            });
+        }
+    }
+
+    None
+}
+
+impl<T> Grid<T> {
+    /// Breadth-first search over orthogonal moves, `can_move(from, to)` decides which steps
+    /// are allowed.
+    pub fn bfs(&self, start: Pos, can_move: impl Fn(&T, &T) -> bool) -> Search<Pos, usize> {
+        bfs(start, |&pos| {
+            self.neighbours4(pos)
+                .filter(|&(_, to)| can_move(&self[pos], to))
+                .map(|(next, _)| next)
+                .collect::<Vec<_>>()
+        })
+    }
+
+    /// Dijkstra over orthogonal moves, `cost(from, to)` returns `None` for blocked steps.
+    pub fn dijkstra<C>(&self, start: Pos, cost: impl Fn(&T, &T) -> Option<C>) -> Search<Pos, C>
+    where
+        C: Copy + Ord + Add<Output = C> + Default,
+    {
+        dijkstra(start, |&pos| {
+            self.neighbours4(pos)
+                .filter_map(|(next, to)| cost(&self[pos], to).map(|cost| (next, cost)))
+                .collect::<Vec<_>>()
+        })
+    }
+
+    /// A* over orthogonal moves from `start` to `goal`, using the manhattan distance as
+    /// heuristic. Every step must cost at least one for the heuristic to stay admissible.
+    pub fn astar(
+        &self,
+        start: Pos,
+        goal: Pos,
+        cost: impl Fn(&T, &T) -> Option<usize>,
+    ) -> Option<(usize, Vec<Pos>)> {
+        astar(
+            start,
+            |&pos| {
+                self.neighbours4(pos)
+                    .filter_map(|(next, to)| cost(&self[pos], to).map(|cost| (next, cost)))
+                    .collect::<Vec<_>>()
+            },
+            |&(row, col)| row.abs_diff(goal.0) + col.abs_diff(goal.1),
+            |&pos| pos == goal,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // a -1- b -1- d
+    //  \         /
+    //   4-- c -1-
+    //
+    // e is not connected.
+    fn weighted(node: &char) -> Vec<(char, u32)> {
+        match node {
+            'a' => vec![('b', 1), ('c', 4)],
+            'b' => vec![('a', 1), ('d', 1)],
+            'c' => vec![('a', 4), ('d', 1)],
+            'd' => vec![('b', 1), ('c', 1)],
+            _ => vec![],
+        }
+    }
+
+    fn unweighted(node: &char) -> Vec<char> {
+        weighted(node).into_iter().map(|(next, _)| next).collect()
+    }
+
+    #[test]
+    fn test_bfs() {
+        let search = bfs('a', unweighted);
+        assert_eq!(search.distance(&'a'), Some(0));
+        assert_eq!(search.distance(&'c'), Some(1));
+        assert_eq!(search.distance(&'d'), Some(2));
+        assert_eq!(search.distance(&'e'), None);
+        assert_eq!(search.path(&'d').unwrap().len(), 3);
+        assert_eq!(search.path(&'e'), None);
+        assert_eq!(search.distances().count(), 4);
+    }
+
+    #[test]
+    fn test_dijkstra() {
+        let search = dijkstra('a', weighted);
+        assert_eq!(search.distance(&'b'), Some(1));
+        assert_eq!(search.distance(&'d'), Some(2));
+        assert_eq!(search.distance(&'c'), Some(3));
+        assert_eq!(search.path(&'c'), Some(vec!['a', 'b', 'd', 'c']));
+        assert_eq!(search.path(&'a'), Some(vec!['a']));
+    }
+
+    #[test]
+    fn test_astar() {
+        assert_eq!(
+            astar('a', weighted, |_| 0, |&node| node == 'c'),
+            Some((3, vec!['a', 'b', 'd', 'c']))
+        );
+        assert_eq!(astar('a', weighted, |_| 0, |&node| node == 'e'), None);
+    }
+
+    #[test]
+    fn test_grid_search() {
+        let grid = Grid::parse("..#.\n.##.\n....\n", |c| c);
+        let open = |_: &char, to: &char| *to == '.';
+
+        let search = grid.bfs((0, 0), open);
+        assert_eq!(search.distance(&(0, 3)), Some(7));
+        assert_eq!(search.distance(&(0, 2)), None);
+        assert_eq!(search.path(&(0, 3)).unwrap().len(), 8);
+
+        let weighted = grid.dijkstra((0, 0), |_, to| (*to == '.').then_some(2));
+        assert_eq!(weighted.distance(&(0, 3)), Some(14));
+
+        let (cost, path) = grid
+            .astar((0, 0), (0, 3), |_, to| (*to == '.').then_some(1))
+            .unwrap();
+        assert_eq!(cost, 7);
+        assert_eq!(path.first(), Some(&(0, 0)));
+        assert_eq!(path.last(), Some(&(0, 3)));
+    }
+}
@@ -0,0 +1,107 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+/// Keeps the `k` largest of all items pushed into it, in O(log k) per item and O(k) memory.
+#[derive(Clone, Debug)]
+pub struct TopK<T> {
+    k: usize,
+    /// min-heap, so the smallest kept item is the one to evict.
+    heap: BinaryHeap<Reverse<T>>,
+}
+
+impl<T: Ord> TopK<T> {
+    pub fn new(k: usize) -> Self {
+        TopK {
+            k,
+            heap: BinaryHeap::with_capacity(k),
+        }
+    }
+
+    pub fn push(&mut self, item: T) {
+        if self.heap.len() < self.k {
+            self.heap.push(Reverse(item));
+        } else if let Some(mut smallest) = self.heap.peek_mut() {
+            // the heap is restored when `smallest` goes out of scope.
+            if item > smallest.0 {
+                *smallest = Reverse(item);
+            }
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.heap.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.heap.is_empty()
+    }
+
+    /// The smallest item kept so far, which anything new has to beat once `k` items are kept.
+    pub fn threshold(&self) -> Option<&T> {
+        self.heap.peek().map(|Reverse(item)| item)
+    }
+
+    /// The kept items, largest first.
+    pub fn into_sorted_vec(self) -> Vec<T> {
+        self.heap
+            .into_sorted_vec()
+            .into_iter()
+            .map(|Reverse(item)| item)
+            .collect()
+    }
+}
+
+impl<T: Ord> Extend<T> for TopK<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, items: I) {
+        for item in items {
+            self.push(item);
+        }
+    }
+}
+
+/// The `k` largest items, largest first.
+pub fn largest<T: Ord>(items: impl IntoIterator<Item = T>, k: usize) -> Vec<T> {
+    let mut top = TopK::new(k);
+    top.extend(items);
+    top.into_sorted_vec()
+}
+
+/// The `k` smallest items, smallest first.
+pub fn smallest<T: Ord>(items: impl IntoIterator<Item = T>, k: usize) -> Vec<T> {
+    largest(items.into_iter().map(Reverse), k)
+        .into_iter()
+        .map(|Reverse(item)| item)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_largest() {
+        let items = [5, 1, 9, 3, 9, 7, 2];
+        assert_eq!(largest(items, 3), vec![9, 9, 7]);
+        assert_eq!(largest(items, 10), vec![9, 9, 7, 5, 3, 2, 1]);
+        assert_eq!(largest(items, 0), Vec::<i32>::new());
+    }
+
+    #[test]
+    fn test_smallest() {
+        assert_eq!(smallest([5, 1, 9, 3, 9, 7, 2], 3), vec![1, 2, 3]);
+        assert_eq!(smallest(["b", "c", "a"], 2), vec!["a", "b"]);
+    }
+
+    #[test]
+    fn test_push() {
+        let mut top = TopK::new(2);
+        assert_eq!(top.threshold(), None);
+
+        top.extend([4, 8, 6]);
+        assert_eq!(top.len(), 2);
+        assert_eq!(top.threshold(), Some(&6));
+
+        top.push(1);
+        assert_eq!(top.into_sorted_vec(), vec![8, 6]);
+    }
+}
@@ -0,0 +1,248 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use super::grid::Grid;
+
+/// Disjoint sets over the elements `0..len`, with path compression and union by rank.
+#[derive(Clone, Debug)]
+pub struct UnionFind {
+    parent: Vec<usize>,
+    rank: Vec<u8>,
+    size: Vec<usize>,
+    count: usize,
+}
+
+impl UnionFind {
+    /// `len` singleton sets.
+    pub fn new(len: usize) -> Self {
+        UnionFind {
+            parent: (0..len).collect(),
+            rank: vec![0; len],
+            size: vec![1; len],
+            count: len,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.parent.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.parent.is_empty()
+    }
+
+    /// Number of disjoint sets.
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Representative of the set containing `x`.
+    pub fn find(&mut self, x: usize) -> usize {
+        let mut root = x;
+        while self.parent[root] != root {
+            root = self.parent[root];
+        }
+
+        // point everything on the way directly at the root.
+        let mut current = x;
+        while self.parent[current] != root {
+            let next = self.parent[current];
+            self.parent[current] = root;
+            current = next;
+        }
+
+        root
+    }
+
+    /// Merges the sets containing `a` and `b`, returns `false` if they already were one.
+    pub fn union(&mut self, a: usize, b: usize) -> bool {
+        let (a, b) = (self.find(a), self.find(b));
+        if a == b {
+            return false;
+        }
+
+        let (root, child) = if self.rank[a] < self.rank[b] {
+            (b, a)
+        } else {
+            (a, b)
+        };
+
+        self.parent[child] = root;
+        self.size[root] += self.size[child];
+        if self.rank[root] == self.rank[child] {
+            self.rank[root] += 1;
+        }
+        self.count -= 1;
+
+        true
+    }
+
+    pub fn connected(&mut self, a: usize, b: usize) -> bool {
+        self.find(a) == self.find(b)
+    }
+
+    /// Size of the set containing `x`.
+    pub fn size(&mut self, x: usize) -> usize {
+        let root = self.find(x);
+        self.size[root]
+    }
+
+    /// Labels every element with the number of its set, numbered `0..count()` in order of
+    /// their first element.
+    pub fn labels(&mut self) -> (Vec<usize>, Vec<usize>) {
+        let mut label_of_root = HashMap::new();
+        let mut sizes = vec![];
+
+        let labels = (0..self.len())
+            .map(|x| {
+                let root = self.find(x);
+                *label_of_root.entry(root).or_insert_with(|| {
+                    sizes.push(self.size[root]);
+                    sizes.len() - 1
+                })
+            })
+            .collect();
+
+        (labels, sizes)
+    }
+}
+
+/// Connected components, each labelled with a number in `0..sizes.len()`.
+#[derive(Clone, Debug)]
+pub struct Components<L> {
+    /// Component label of every node.
+    pub labels: L,
+    /// Number of nodes in each component, indexed by label.
+    pub sizes: Vec<usize>,
+}
+
+impl<L> Components<L> {
+    pub fn count(&self) -> usize {
+        self.sizes.len()
+    }
+
+    /// Sizes of all components, largest first.
+    pub fn sizes_descending(&self) -> Vec<usize> {
+        let mut sizes = self.sizes.clone();
+        sizes.sort_unstable_by(|a, b| b.cmp(a));
+        sizes
+    }
+}
+
+/// Connected components of an undirected graph given as its nodes and a neighbour function.
+/// Neighbours outside of `nodes` are ignored.
+pub fn components<N, I>(
+    nodes: impl IntoIterator<Item = N>,
+    mut neighbours: impl FnMut(&N) -> I,
+) -> Components<HashMap<N, usize>>
+where
+    N: Eq + Hash + Clone,
+    I: IntoIterator<Item = N>,
+{
+    let nodes: Vec<N> = nodes.into_iter().collect();
+    let index: HashMap<N, usize> = nodes
+        .iter()
+        .enumerate()
+        .map(|(i, node)| (node.clone(), i))
+        .collect();
+
+    let mut sets = UnionFind::new(nodes.len());
+    for (i, node) in nodes.iter().enumerate() {
+        for neighbour in neighbours(node) {
+            if let Some(&j) = index.get(&neighbour) {
+                sets.union(i, j);
+            }
+        }
+    }
+
+    let (labels, sizes) = sets.labels();
+
+    Components {
+        labels: nodes.into_iter().zip(labels).collect(),
+        sizes,
+    }
+}
+
+impl<T> Grid<T> {
+    /// Labels the regions of orthogonally adjacent cells for which `connected(a, b)` holds,
+    /// e.g. `|a, b| a == b` for areas of the same plant.
+    pub fn components(&self, connected: impl Fn(&T, &T) -> bool) -> Components<Grid<usize>> {
+        let cols = self.cols();
+        let mut sets = UnionFind::new(self.rows() * cols);
+
+        for ((row, col), value) in self.iter() {
+            // right and down neighbours are enough to see every edge once.
+            for (d_row, d_col) in [(0, 1), (1, 0)] {
+                if let Some(next) = self.offset((row, col), (d_row, d_col)) {
+                    if connected(value, &self[next]) {
+                        sets.union(row * cols + col, next.0 * cols + next.1);
+                    }
+                }
+            }
+        }
+
+        let (labels, sizes) = sets.labels();
+
+        Components {
+            labels: Grid::from_rows(labels.chunks(cols.max(1)).map(|row| row.to_vec())),
+            sizes,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_union_find() {
+        let mut sets = UnionFind::new(6);
+        assert_eq!(sets.count(), 6);
+
+        assert!(sets.union(0, 1));
+        assert!(sets.union(2, 3));
+        assert!(sets.union(1, 3));
+        assert!(!sets.union(0, 2));
+
+        assert!(sets.connected(0, 3));
+        assert!(!sets.connected(0, 4));
+        assert_eq!(sets.size(2), 4);
+        assert_eq!(sets.size(5), 1);
+        assert_eq!(sets.count(), 3);
+        assert_eq!(sets.labels(), (vec![0, 0, 0, 0, 1, 2], vec![4, 1, 1]));
+    }
+
+    #[test]
+    fn test_components() {
+        // 1 - 2 - 3   4 - 5   6
+        let edges = [(1, 2), (2, 3), (4, 5)];
+        let result = components(1..=6, |&node| {
+            edges
+                .iter()
+                .filter_map(move |&(a, b)| match node {
+                    _ if a == node => Some(b),
+                    _ if b == node => Some(a),
+                    _ => None,
+                })
+                .chain([99])
+                .collect::<Vec<_>>()
+        });
+
+        assert_eq!(result.count(), 3);
+        assert_eq!(result.labels[&1], result.labels[&3]);
+        assert_ne!(result.labels[&3], result.labels[&4]);
+        assert_eq!(result.sizes_descending(), vec![3, 2, 1]);
+    }
+
+    #[test]
+    fn test_grid_components() {
+        let grid = Grid::parse("AAB\nABB\nCCB\n", |c| c);
+        let result = grid.components(|a, b| a == b);
+
+        assert_eq!(result.count(), 3);
+        assert_eq!(result.labels[(0, 0)], result.labels[(1, 0)]);
+        assert_eq!(result.labels[(0, 2)], result.labels[(2, 2)]);
+        assert_eq!(result.sizes[result.labels[(1, 1)]], 4);
+        assert_eq!(result.sizes_descending(), vec![4, 3, 2]);
+    }
+}
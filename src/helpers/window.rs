@@ -0,0 +1,132 @@
+use std::collections::{HashMap, VecDeque};
+use std::hash::Hash;
+
+/// A window of consecutive items, `start` being the index of its first item.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Window<T> {
+    pub start: usize,
+    pub items: Vec<T>,
+}
+
+impl<T> Window<T> {
+    /// Index one past the last item of the window.
+    pub fn end(&self) -> usize {
+        self.start + self.items.len()
+    }
+}
+
+/// Finds the first `k` consecutive items that are pairwise distinct, in a single pass.
+///
+/// Remembers where each item was last seen, so the window start only ever moves forward and
+/// every item is looked at once: O(n) instead of re-checking the whole window on every step.
+pub fn first_distinct_window<T, I>(items: I, k: usize) -> Option<Window<T>>
+where
+    T: Eq + Hash + Clone,
+    I: IntoIterator<Item = T>,
+{
+    let mut last_seen: HashMap<T, usize> = HashMap::new();
+    let mut window: VecDeque<T> = VecDeque::with_capacity(k);
+    let mut start = 0;
+
+    if k == 0 {
+        return Some(Window {
+            start,
+            items: vec![],
+        });
+    }
+
+    for (index, item) in items.into_iter().enumerate() {
+        if let Some(&previous) = last_seen.get(&item) {
+            if previous >= start {
+                window.drain(..=previous - start);
+                start = previous + 1;
+            }
+        }
+
+        last_seen.insert(item.clone(), index);
+        window.push_back(item);
+
+        if window.len() == k {
+            return Some(Window {
+                start,
+                items: window.into(),
+            });
+        }
+    }
+
+    None
+}
+
+/// Byte specialization of [`first_distinct_window`], using a lookup table instead of hashing.
+pub fn first_distinct_byte_window(bytes: &[u8], k: usize) -> Option<Window<u8>> {
+    // index + 1 of the last occurrence of every byte value, 0 if not seen yet.
+    let mut last_seen = [0_usize; 256];
+    let mut start = 0;
+
+    if k == 0 {
+        return Some(Window {
+            start,
+            items: vec![],
+        });
+    }
+
+    for (index, &byte) in bytes.iter().enumerate() {
+        start = start.max(last_seen[byte as usize]);
+        last_seen[byte as usize] = index + 1;
+
+        if index + 1 - start == k {
+            return Some(Window {
+                start,
+                items: bytes[start..=index].to_vec(),
+            });
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_first_distinct_window() {
+        let window = first_distinct_window("mjqjpqmgbljsphdztnvjfqwrcgsmlb".chars(), 4).unwrap();
+        assert_eq!(window.start, 3);
+        assert_eq!(window.end(), 7);
+        assert_eq!(window.items, vec!['j', 'p', 'q', 'm']);
+
+        let numbers = [1, 2, 1, 3, 3, 4, 5, 3];
+        assert_eq!(
+            first_distinct_window(numbers, 3),
+            Some(Window {
+                start: 1,
+                items: vec![2, 1, 3]
+            })
+        );
+        assert_eq!(first_distinct_window(numbers, 4), None);
+        assert_eq!(first_distinct_window(numbers, 0).unwrap().end(), 0);
+    }
+
+    #[test]
+    fn test_first_distinct_byte_window() {
+        let window = first_distinct_byte_window(b"nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg", 4).unwrap();
+        assert_eq!(window.end(), 10);
+        assert_eq!(window.items, b"rfnt".to_vec());
+
+        assert_eq!(first_distinct_byte_window(b"aab", 2).unwrap().start, 1);
+        assert_eq!(first_distinct_byte_window(b"aaa", 2), None);
+    }
+
+    #[test]
+    fn test_byte_window_matches_generic() {
+        let input = b"zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw";
+
+        for k in 1..=16 {
+            assert_eq!(
+                first_distinct_byte_window(input, k),
+                first_distinct_window(input.iter().copied(), k)
+            );
+        }
+    }
+}
@@ -6,8 +6,11 @@
 use std::env;
 use std::fs;
 
+pub mod answer;
 pub mod helpers;
 
+pub use answer::Answer;
+
 pub const ANSI_ITALIC: &str = "\x1b[3m";
 pub const ANSI_BOLD: &str = "\x1b[1m";
 pub const ANSI_RESET: &str = "\x1b[0m";
@@ -15,7 +18,7 @@ pub const ANSI_RESET: &str = "\x1b[0m";
 #[macro_export]
 macro_rules! solve {
     ($part:expr, $solver:ident, $input:expr) => {{
-        use advent_of_code::{ANSI_BOLD, ANSI_ITALIC, ANSI_RESET};
+        use advent_of_code::{Answer, ANSI_BOLD, ANSI_ITALIC, ANSI_RESET};
         use std::fmt::Display;
         use std::time::Instant;
 
@@ -23,11 +26,16 @@ macro_rules! solve {
             let timer = Instant::now();
             let result = func(input);
             let elapsed = timer.elapsed();
-            match result {
-                Some(result) => {
+            match result.map(|result| Answer::from_display(&result)) {
+                // multi-line answers (e.g. ASCII art) get the timing on a separate line.
+                Some(answer) if answer.is_multiline() => {
+                    println!("{}", answer);
+                    println!("{}(elapsed: {:.2?}){}", ANSI_ITALIC, elapsed, ANSI_RESET);
+                }
+                Some(answer) => {
                     println!(
                         "{} {}(elapsed: {:.2?}){}",
-                        result, ANSI_ITALIC, elapsed, ANSI_RESET
+                        answer, ANSI_ITALIC, elapsed, ANSI_RESET
                     );
                 }
                 None => {
@@ -77,6 +85,61 @@ pub fn parse_exec_time(output: &str) -> f64 {
     })
 }
 
+fn is_part_header(line: &str) -> bool {
+    line.starts_with("🎄 ") && line.contains("Part ")
+}
+
+/// Reads the answers back from the output of `solve!`, one entry per part.
+/// Parts that printed "not solved." are `None`.
+pub fn parse_answers(output: &str) -> Vec<Option<Answer>> {
+    let mut answers = vec![];
+    let mut pending: Option<Vec<&str>> = None;
+
+    for line in output.lines() {
+        if is_part_header(line) {
+            pending = Some(vec![]);
+            continue;
+        }
+
+        let Some(answer_lines) = pending.as_mut() else {
+            continue;
+        };
+
+        if line == "not solved." {
+            answers.push(None);
+            pending = None;
+        } else if let Some((answer, _)) = line.split_once("(elapsed: ") {
+            let answer = answer.trim_end_matches(ANSI_ITALIC).trim_end();
+            if !answer.is_empty() {
+                answer_lines.push(answer);
+            }
+            answers.push(Some(answer_lines.join("\n").parse().unwrap()));
+            pending = None;
+        } else {
+            answer_lines.push(line);
+        }
+    }
+
+    answers
+}
+
+/// Extracts the panic message from the stderr of a failed solution run, e.g. the
+/// [`helpers::diagnostic::Diagnostic`] of an input that failed to parse.
+pub fn parse_panic_message(stderr: &str) -> Option<String> {
+    let message: Vec<&str> = stderr
+        .lines()
+        .skip_while(|line| !line.contains("panicked at"))
+        .skip(1)
+        .take_while(|line| !line.starts_with("note: ") && !line.starts_with("stack backtrace:"))
+        .collect();
+
+    if message.is_empty() {
+        None
+    } else {
+        Some(message.join("\n"))
+    }
+}
+
 /// copied from: https://github.com/rust-lang/rust/blob/1.64.0/library/std/src/macros.rs#L328-L333
 #[cfg(test)]
 macro_rules! assert_approx_eq {
@@ -122,4 +185,39 @@ mod tests {
             10400.50_f64
         );
     }
+
+    #[test]
+    fn test_parse_panic_message() {
+        let stderr = "    Finished release [optimized] target(s) in 0.02s\n     Running `target/release/05`\nthread 'main' panicked at src/bin/05.rs:160:5:\nerror: expected \" from \"\n --> line 6, column 7\nnote: run with `RUST_BACKTRACE=1` environment variable to display a backtrace\n";
+
+        assert_eq!(
+            parse_panic_message(stderr),
+            Some("error: expected \" from \"\n --> line 6, column 7".to_string())
+        );
+        assert_eq!(
+            parse_panic_message("error: no bin target named `09`."),
+            None
+        );
+    }
+
+    #[test]
+    fn test_parse_answers() {
+        assert_eq!(
+            parse_answers(&format!(
+                "🎄 {}Part 1{} 🎄\n24000 {}(elapsed: 74.13ns){}\n🎄 {}Part 2{} 🎄\nnot solved.",
+                ANSI_BOLD, ANSI_RESET, ANSI_ITALIC, ANSI_RESET, ANSI_BOLD, ANSI_RESET
+            )),
+            vec![Some(Answer::Number(24000)), None]
+        );
+
+        assert_eq!(
+            parse_answers(
+                "🎄 Part 1 🎄\nCMZ (elapsed: 1.45ms)\n🎄 Part 2 🎄\n#..#\n####\n(elapsed: 2.00µs)"
+            ),
+            vec![
+                Some(Answer::Text("CMZ".into())),
+                Some(Answer::Text("#..#\n####".into()))
+            ]
+        );
+    }
 }
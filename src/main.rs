@@ -2,43 +2,67 @@
  * This file contains template code.
  * There is no need to edit this file unless you want to change template functionality.
  */
-use advent_of_code::{ANSI_BOLD, ANSI_ITALIC, ANSI_RESET};
+use advent_of_code::{Answer, ANSI_BOLD, ANSI_ITALIC, ANSI_RESET};
 use std::process::Command;
 
+struct DayResult {
+    answers: Vec<Option<Answer>>,
+    elapsed_ms: f64,
+}
+
+fn run_day(day: u8) -> DayResult {
+    let day = format!("{:02}", day);
+
+    let cmd = Command::new("cargo")
+        .args(["run", "--release", "--bin", &day])
+        .output()
+        .unwrap();
+
+    println!("----------");
+    println!("{}| Day {} |{}", ANSI_BOLD, day, ANSI_RESET);
+    println!("----------");
+
+    let output = String::from_utf8(cmd.stdout).unwrap();
+    let is_empty = output.is_empty();
+
+    println!(
+        "{}",
+        if is_empty {
+            "Not solved."
+        } else {
+            output.trim()
+        }
+    );
+
+    if !cmd.status.success() {
+        if let Some(message) =
+            advent_of_code::parse_panic_message(&String::from_utf8_lossy(&cmd.stderr))
+        {
+            println!("Failed:\n{}", message);
+        }
+    }
+
+    DayResult {
+        answers: advent_of_code::parse_answers(&output),
+        elapsed_ms: if is_empty {
+            0_f64
+        } else {
+            advent_of_code::parse_exec_time(&output)
+        },
+    }
+}
+
 fn main() {
-    let total: f64 = (1..=25)
-        .map(|day| {
-            let day = format!("{:02}", day);
-
-            let cmd = Command::new("cargo")
-                .args(["run", "--release", "--bin", &day])
-                .output()
-                .unwrap();
-
-            println!("----------");
-            println!("{}| Day {} |{}", ANSI_BOLD, day, ANSI_RESET);
-            println!("----------");
-
-            let output = String::from_utf8(cmd.stdout).unwrap();
-            let is_empty = output.is_empty();
-
-            println!(
-                "{}",
-                if is_empty {
-                    "Not solved."
-                } else {
-                    output.trim()
-                }
-            );
-
-            if is_empty {
-                0_f64
-            } else {
-                advent_of_code::parse_exec_time(&output)
-            }
-        })
-        .sum();
+    let results: Vec<DayResult> = (1..=25).map(run_day).collect();
+
+    let total: f64 = results.iter().map(|result| result.elapsed_ms).sum();
+    let solved = results
+        .iter()
+        .flat_map(|result| &result.answers)
+        .filter(|answer| answer.is_some())
+        .count();
 
+    println!("{}Solved:{} {} parts", ANSI_BOLD, ANSI_RESET, solved);
     println!(
         "{}Total:{} {}{:.2}ms{}",
         ANSI_BOLD, ANSI_RESET, ANSI_ITALIC, total, ANSI_RESET